@@ -0,0 +1,8 @@
+use w2::samples::bump_mapping::bump_mapping;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = bump_mapping(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,57 @@
+use std::{rc::Rc, time::Instant};
+
+use w2::{
+    hittable::{
+        bvh::BVHNode, hittable_list::HittableList, sphere::Sphere, wide_bvh::WideBVHNode, Hittable,
+    },
+    material::{lambertian::Lambertian, Material},
+    primitive::{color::Color, interval::Interval, ray::Ray, vec3::Vec3},
+};
+
+const SPHERE_CNT: usize = 20_000;
+const RAY_CNT: usize = 200_000;
+
+// Compares the binary BVHNode traversal against the wide WideBVHNode traversal.
+// Run with `cargo run --release --bin bvh_bench`.
+fn main() {
+    let material: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let mut world = HittableList::new();
+    for _ in 0..SPHERE_CNT {
+        world.add(Rc::new(Sphere::new(
+            Vec3::random_uniform(-50.0, 50.0),
+            0.3,
+            material.clone(),
+        )));
+    }
+
+    let rays: Vec<Ray> = (0..RAY_CNT)
+        .map(|_| Ray::new(Vec3::random_uniform(-60.0, 60.0), Vec3::random_unit(), 0.0))
+        .collect();
+
+    let start = Instant::now();
+    let binary = BVHNode::from(world.objects.as_slice());
+    eprintln!("BVHNode build: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let wide = WideBVHNode::from(world.objects.as_slice());
+    eprintln!("WideBVHNode build: {:?}", start.elapsed());
+
+    bench("BVHNode", &binary, &rays);
+    bench("WideBVHNode", &wide, &rays);
+}
+
+fn bench(name: &str, world: &dyn Hittable, rays: &[Ray]) {
+    let start = Instant::now();
+    let hits = rays
+        .iter()
+        .filter(|r| world.hit(r, Interval::new(0.001, f64::INFINITY)).is_some())
+        .count();
+    let elapsed = start.elapsed();
+    eprintln!(
+        "{}: {} hits, {:?} total, {:.1} ns/ray",
+        name,
+        hits,
+        elapsed,
+        elapsed.as_nanos() as f64 / rays.len() as f64
+    );
+}
@@ -0,0 +1,8 @@
+use w2::samples::cellular::cellular;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = cellular(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::cloud::cloud;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = cloud(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::csg_shapes::csg_shapes;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = csg_shapes(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::cutout::cutout;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = cutout(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
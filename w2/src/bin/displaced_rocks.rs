@@ -0,0 +1,8 @@
+use w2::samples::displaced_rocks::displaced_rocks;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = displaced_rocks(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
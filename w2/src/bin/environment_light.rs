@@ -0,0 +1,8 @@
+use w2::samples::environment_light::environment_light;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = environment_light(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::furball::furball;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = furball(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::material_library::material_library;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = material_library(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
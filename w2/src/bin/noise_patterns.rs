@@ -0,0 +1,8 @@
+use w2::samples::noise_patterns::noise_patterns;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = noise_patterns(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::primitives::primitives;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = primitives(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::sdf_shapes::sdf_shapes;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = sdf_shapes(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::smoke_spheres::smoke_spheres;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = smoke_spheres(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
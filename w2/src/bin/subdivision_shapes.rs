@@ -0,0 +1,8 @@
+use w2::samples::subdivision_shapes::subdivision_shapes;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = subdivision_shapes(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
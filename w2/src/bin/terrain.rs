@@ -0,0 +1,8 @@
+use w2::samples::terrain::terrain;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = terrain(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+use w2::samples::texture_graph::texture_graph;
+
+fn main() {
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = texture_graph(&mut out) {
+        eprintln!("Error: {}", e);
+    }
+}
@@ -1,9 +1,18 @@
+use std::rc::Rc;
+
 use derive_builder::Builder;
 use rand_distr::{Distribution, Uniform};
 
 use crate::{
-    hittable::Hittable,
-    primitive::{color::Color, interval::Interval, point3::Point3, ray::Ray, vec3::Vec3},
+    hittable::{HitRecord, Hittable},
+    light::environment::EnvironmentLight,
+    primitive::{
+        color::Color,
+        interval::Interval,
+        point3::Point3,
+        ray::{Ray, RayDifferentials},
+        vec3::Vec3,
+    },
 };
 
 #[derive(Builder, Debug)]
@@ -20,6 +29,9 @@ pub struct CameraOptions {
     #[builder(default = "10.")]
     pub focus_dist: f64,
     pub time_range: Interval,
+    // Lights the scene from all around. Without one, rays that escape see a sky gradient.
+    #[builder(default, setter(strip_option))]
+    pub environment: Option<Rc<EnvironmentLight>>,
 }
 
 impl CameraOptions {
@@ -30,7 +42,6 @@ impl CameraOptions {
 
 #[derive(Debug)]
 pub struct Camera {
-    aspect_ratio: f64,
     image_width: u32,
     image_height: u32,
 
@@ -38,10 +49,7 @@ pub struct Camera {
     pixel_samples_scale: f64,
     max_depth: u32, // recursion depth for shadow rays
 
-    vfov: f64,
     lookfrom: Point3, // Camera center
-    lookat: Vec3,     // Camera target
-    vup: Vec3,        // viewport orientation vector(world up)
 
     pixel00_loc: Point3, // location of the top-left corner of the viewport
     pixel_delta_u: Vec3,
@@ -49,12 +57,13 @@ pub struct Camera {
 
     // Depth of field effect
     defocus_angle: f64, // angle of camera lens cone
-    focus_dist: f64,    // distance of lookfrom to the focus plane
     defocus_u: Vec3,
     defocus_v: Vec3,
 
     // time range of the current frame
     time_step: Uniform<f64>,
+
+    environment: Option<Rc<EnvironmentLight>>,
 }
 
 impl Camera {
@@ -71,6 +80,7 @@ impl Camera {
             defocus_angle,
             focus_dist,
             time_range,
+            environment,
         }: CameraOptions,
     ) -> Self {
         let mut image_height = (image_width as f64 / aspect_ratio) as u32;
@@ -105,24 +115,20 @@ impl Camera {
         let sampler = Uniform::new(time_range.start, time_range.end);
 
         Self {
-            aspect_ratio,
             image_width,
             image_height,
             samples_per_pixel,
             pixel_samples_scale: 1.0 / (samples_per_pixel as f64),
             max_depth,
-            vfov,
             lookfrom,
-            lookat,
-            vup,
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
             defocus_angle,
-            focus_dist,
             defocus_u,
             defocus_v,
             time_step: sampler,
+            environment,
         }
     }
 
@@ -139,7 +145,7 @@ impl Camera {
                 let mut pixel_color = Color::new(0, 0, 0);
                 for _ in 0..self.samples_per_pixel {
                     let r = self.get_ray(i as usize, j as usize);
-                    pixel_color += Camera::ray_color(&r, world, self.max_depth);
+                    pixel_color += self.ray_color(&r, world, self.max_depth, None);
                 }
                 pixel_color *= self.pixel_samples_scale;
                 pixel_color.write_color(out)?;
@@ -173,36 +179,110 @@ impl Camera {
         let ray_dir = pixel_sample - ray_origin;
         let time = self.time_step.sample(&mut rand::thread_rng());
 
+        // rays through the neighbouring pixels, narrowed as more samples share each pixel
+        let differentials = RayDifferentials {
+            rx_origin: ray_origin,
+            rx_direction: ray_dir + self.pixel_delta_u,
+            ry_origin: ray_origin,
+            ry_direction: ray_dir + self.pixel_delta_v,
+        };
+        let scale = (1.0 / (self.samples_per_pixel as f64).sqrt()).max(0.125);
+
         Ray::new(ray_origin, ray_dir, time)
+            .with_differentials(Some(differentials.scaled(ray_origin, ray_dir, scale)))
     }
 
-    fn ray_color(r: &Ray, world: &dyn Hittable, max_depth: u32) -> Color {
+    // bsdf_pdf is the density with which the previous bounce picked r's direction,
+    // or None when it could not have been chosen by a light sample (camera rays, mirrors).
+    fn ray_color(
+        &self,
+        r: &Ray,
+        world: &dyn Hittable,
+        max_depth: u32,
+        bsdf_pdf: Option<f64>,
+    ) -> Color {
         if max_depth == 0 {
             return Color::new(0, 0, 0);
         }
 
-        if let Some(rec) = world.hit(
+        let Some(rec) = world.hit_opaque(
             r,
             Interval {
                 start: 0.001,
                 end: f64::INFINITY,
             },
-        ) {
-            let mut scattered = Ray::new(Vec3::zero(), Vec3::zero(), r.time());
-            let mut attenuation = Color::new(0, 0, 0);
-            if rec
-                .material
-                .scatter(r, &rec, &mut attenuation, &mut scattered)
-                && max_depth > 0
-            {
-                attenuation * Camera::ray_color(&scattered, world, max_depth - 1)
-            } else {
-                Color::new(0, 0, 0)
-            }
-        } else {
-            let unit_direction = r.direction().unit();
-            let t = 0.5 * (unit_direction.y + 1.0);
-            Color::new(1, 1, 1) * (1.0 - t) + Color::new(0.5, 0.7, 1.0) * t
+        ) else {
+            return match &self.environment {
+                Some(env) => {
+                    let radiance = env.radiance(&r.direction());
+                    match bsdf_pdf {
+                        // this direction could also have been found by a light sample
+                        Some(pdf) => radiance * power_heuristic(pdf, env.pdf(&r.direction())),
+                        None => radiance,
+                    }
+                }
+                None => {
+                    let unit_direction = r.direction().unit();
+                    let t = 0.5 * (unit_direction.y + 1.0);
+                    Color::new(1, 1, 1) * (1.0 - t) + Color::new(0.5, 0.7, 1.0) * t
+                }
+            };
+        };
+
+        let mut scattered = Ray::new(Vec3::zero(), Vec3::zero(), r.time());
+        let mut attenuation = Color::new(0, 0, 0);
+        if !rec
+            .material
+            .scatter(r, &rec, &mut attenuation, &mut scattered)
+        {
+            return Color::new(0, 0, 0);
+        }
+        let scattered_pdf = rec.material.scattering_pdf(r, &rec, &scattered);
+
+        let direct = match (&self.environment, scattered_pdf) {
+            (Some(env), Some(_)) => self.sample_environment(env, r, &rec, world, attenuation),
+            _ => Color::new(0, 0, 0),
+        };
+        direct + attenuation * self.ray_color(&scattered, world, max_depth - 1, scattered_pdf)
+    }
+
+    // Light reaching the hit point straight from the environment, through one light sample,
+    // weighted against finding the same direction by scattering.
+    fn sample_environment(
+        &self,
+        env: &EnvironmentLight,
+        r: &Ray,
+        rec: &HitRecord,
+        world: &dyn Hittable,
+        attenuation: Color,
+    ) -> Color {
+        let (direction, light_pdf) = env.sample();
+        if light_pdf <= 0.0 {
+            return Color::new(0, 0, 0);
+        }
+        let shadow_ray = Ray::new(rec.p, direction, r.time());
+        let material_pdf = match rec.material.scattering_pdf(r, rec, &shadow_ray) {
+            Some(pdf) if pdf > 0.0 => pdf,
+            _ => return Color::new(0, 0, 0),
+        };
+        let shadow_t = Interval::new(0.001, f64::INFINITY);
+        let transmittance = world.transmittance(&shadow_ray, shadow_t);
+        if transmittance <= 0.0 {
+            return Color::new(0, 0, 0);
         }
+        attenuation
+            * env.radiance(&direction)
+            * (transmittance * material_pdf / light_pdf * power_heuristic(light_pdf, material_pdf))
+    }
+}
+
+// Multiple importance sampling weight of a sample drawn with density pdf,
+// when another strategy could have drawn it with density other_pdf.
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0.0 {
+        a / (a + b)
+    } else {
+        0.0
     }
 }
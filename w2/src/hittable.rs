@@ -1,21 +1,32 @@
-use std::rc::Rc;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    rc::Rc,
+};
 
 use aabb::AABB;
 
 use crate::{
     material::Material,
-    primitive::{interval::Interval, point3::Point3, ray::Ray, vec3::Vec3},
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+    texture::Footprint,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HitRecord {
     pub p: Point3,
-    pub normal: Vec3,
+    pub normal: Vec3, // geometric normal, facing against the ray
+    // normal used for shading, on the same side as `normal`.
+    // It differs from it with interpolated vertex normals, and bump or normal mapping.
+    pub shading_normal: Vec3,
     pub material: Rc<dyn Material>,
     pub t: f64, // ray parameter
     pub u: f64, // texture coordinate
     pub v: f64, // texture coordinate
     pub front_face: bool,
+    pub dpdu: Vec3, // change of the hit point along u
+    pub dpdv: Vec3, // change of the hit point along v
+    // orthonormal frame around shading_normal, with u following dpdu
+    pub shading_basis: ONB,
 }
 
 impl HitRecord {
@@ -29,17 +40,115 @@ impl HitRecord {
         material: Rc<dyn Material>,
     ) -> Self {
         let (front_face, normal) = Self::face_normal(ray, outward_normal);
-        // TODO: properly calculate u/v
+        // Primitives with a parameterization replace these with with_tangents.
+        let frame = ONB::new(&outward_normal);
 
         Self {
             p,
             normal,
+            shading_normal: normal,
             material,
             t,
             u,
             v,
             front_face,
+            dpdu: frame.u,
+            dpdv: frame.v,
+            shading_basis: ONB::from_normal_tangent(&normal, &frame.u),
+        }
+    }
+    // A record for a scattering event inside a medium, which has no surface
+    // and so no meaningful normal or texture coordinates.
+    fn in_medium(p: Point3, t: f64, material: Rc<dyn Material>) -> Self {
+        let normal = Vec3::new(1.0, 0.0, 0.0); // arbitrary
+        let frame = ONB::new(&normal);
+        Self {
+            p,
+            normal,
+            shading_normal: normal,
+            material,
+            t,
+            u: 0.0,
+            v: 0.0,
+            front_face: true, // also arbitrary
+            dpdu: frame.u,
+            dpdv: frame.v,
+            shading_basis: frame,
+        }
+    }
+    fn with_tangents(mut self, dpdu: Vec3, dpdv: Vec3) -> Self {
+        self.dpdu = dpdu;
+        self.dpdv = dpdv;
+        self.shading_basis = ONB::from_normal_tangent(&self.shading_normal, &dpdu);
+        self
+    }
+    // Replaces the shading normal, flipping it onto the side of the geometric normal.
+    pub fn with_shading_normal(mut self, n: Vec3) -> Self {
+        let n = n.unit();
+        self.shading_normal = if n.dot(&self.normal) < 0.0 { -n } else { n };
+        self.shading_basis = ONB::from_normal_tangent(&self.shading_normal, &self.dpdu);
+        self
+    }
+    // Where the ray's differentials cross the tangent plane at the hit point,
+    // and the matching change in texture coordinates.
+    // None if the ray carries no differentials, or they run parallel to the surface.
+    pub fn footprint(&self, r: &Ray) -> Option<Footprint> {
+        let diff = r.differentials()?;
+        let n = self.normal;
+        let plane_offset = |origin: Point3, direction: Vec3| {
+            let denom = n.dot(&direction);
+            if denom.abs() < 1e-12 {
+                return None;
+            }
+            let t = n.dot(&(self.p - origin)) / denom;
+            Some(origin + direction * t - self.p)
+        };
+        let dpdx = plane_offset(diff.rx_origin, diff.rx_direction)?;
+        let dpdy = plane_offset(diff.ry_origin, diff.ry_direction)?;
+
+        // least squares solution of dpdu * du + dpdv * dv = dp
+        let (a00, a01, a11) = (
+            self.dpdu.dot(&self.dpdu),
+            self.dpdu.dot(&self.dpdv),
+            self.dpdv.dot(&self.dpdv),
+        );
+        let det = a00 * a11 - a01 * a01;
+        let solve = |dp: Vec3| {
+            if det.abs() < 1e-20 {
+                return (0.0, 0.0);
+            }
+            let (b0, b1) = (self.dpdu.dot(&dp), self.dpdv.dot(&dp));
+            ((a11 * b0 - a01 * b1) / det, (a00 * b1 - a01 * b0) / det)
+        };
+        let (dudx, dvdx) = solve(dpdx);
+        let (dudy, dvdy) = solve(dpdy);
+        Some(Footprint {
+            dudx,
+            dvdx,
+            dudy,
+            dvdy,
+            dpdx,
+            dpdy,
+        })
+    }
+    // Whether the ray passes through the surface here, where the material is not fully opaque.
+    // Partial opacity is decided by hashing the ray and the hit rather than by a random number,
+    // so that the same hit gets the same answer however many times it is tested.
+    pub fn is_cut_out(&self, r: &Ray) -> bool {
+        let opacity = self.material.opacity(self);
+        if opacity >= 1.0 {
+            return false;
+        }
+        if opacity <= 0.0 {
+            return true;
         }
+        let mut hasher = DefaultHasher::new();
+        let (o, d) = (r.origin(), r.direction());
+        [o.x, o.y, o.z, d.x, d.y, d.z, self.t]
+            .iter()
+            .for_each(|x| x.to_bits().hash(&mut hasher));
+        let xi = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+        xi >= opacity
     }
     fn face_normal(r: &Ray, outward_normal: Vec3) -> (bool, Vec3) {
         let front_face = r.direction().dot(&outward_normal) < 0.0;
@@ -50,27 +159,68 @@ impl HitRecord {
         };
         (front_face, normal)
     }
-    fn min(self, other: Option<Self>) -> Self {
-        match other {
-            None => self,
-            Some(other) => {
-                if self.t < other.t {
-                    self
-                } else {
-                    other
-                }
-            }
-        }
-    }
 }
 
+// Relative distance skipped after each crossing in Hittable::hit_all and Hittable::hit_opaque.
+const CROSSING_EPSILON: f64 = 1e-9;
+
 pub trait Hittable: std::fmt::Debug {
     fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord>;
     fn bounding_box(&self) -> AABB;
+
+    // Returns every crossing of the ray with the surface within ray_t, sorted by t.
+    // For closed objects, front_face tells whether the ray enters (true) or leaves the object.
+    // The default implementation repeatedly asks hit() for the next crossing.
+    fn hit_all(&self, r: &Ray, mut ray_t: Interval) -> Vec<HitRecord> {
+        let mut records = Vec::new();
+        while let Some(rec) = self.hit(r, ray_t) {
+            // step past the crossing so that the same root is not found again
+            ray_t.start = rec.t + CROSSING_EPSILON * rec.t.abs().max(1.0);
+            records.push(rec);
+        }
+        records
+    }
+
+    // Like hit(), but skips the crossings the ray passes through because of cutout materials.
+    // Aggregates call it on their children, so that a cut out hit does not hide those behind it.
+    fn hit_opaque(&self, r: &Ray, mut ray_t: Interval) -> Option<HitRecord> {
+        while let Some(rec) = self.hit(r, ray_t) {
+            if !rec.is_cut_out(r) {
+                return Some(rec);
+            }
+            ray_t.start = rec.t + CROSSING_EPSILON * rec.t.abs().max(1.0);
+        }
+        None
+    }
+
+    // Fraction of light passing straight through along the ray within ray_t, as for shadow rays.
+    // Surfaces block all of it where they are hit; media let part of it through,
+    // and aggregates multiply what their children let through.
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        if self.hit_opaque(r, ray_t).is_some() {
+            0.0
+        } else {
+            1.0
+        }
+    }
     // fn update_bounding_box(&self, time_range: Interval);
 }
 
 pub mod aabb;
 pub mod bvh;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod curve;
+pub mod cylinder;
+pub mod density_grid;
+pub mod disk;
+pub mod heightfield;
+pub mod heterogeneous_medium;
 pub mod hittable_list;
+pub mod plane;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
+pub mod triangle;
+pub mod wide_bvh;
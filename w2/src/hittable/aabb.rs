@@ -1,7 +1,8 @@
 use crate::primitive::{
-    interval::{Interval, EMPTY_INTERVAL},
+    interval::{Interval, EMPTY_INTERVAL, UNIVERSE_INTERVAL},
     point3::Point3,
     ray::Ray,
+    vec3::Vec3,
 };
 
 // Axis-Aligned Bounding Box
@@ -14,13 +15,24 @@ pub struct AABB {
 
 impl AABB {
     pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
-        Self { x, y, z }
+        Self { x, y, z }.pad_to_minimums()
     }
     pub fn new_from_points(p0: Point3, p1: Point3) -> Self {
         let x = Interval::new(p0.x, p1.x).reorder();
         let y = Interval::new(p0.y, p1.y).reorder();
         let z = Interval::new(p0.z, p1.z).reorder();
-        Self { x, y, z }
+        Self { x, y, z }.pad_to_minimums()
+    }
+    // Tight box around a flat disk.
+    // Along each axis, the disk extends by radius * sin(angle between the axis and the normal).
+    pub fn new_from_disk(center: Point3, normal: &Vec3, radius: f64) -> Self {
+        let n = normal.unit();
+        let extent = Vec3::new(
+            radius * (1.0 - n.x * n.x).max(0.0).sqrt(),
+            radius * (1.0 - n.y * n.y).max(0.0).sqrt(),
+            radius * (1.0 - n.z * n.z).max(0.0).sqrt(),
+        );
+        Self::new_from_points(center - extent, center + extent)
     }
     pub fn surrounding_box(box0: &AABB, box1: &AABB) -> Self {
         let x = box0.x.merge(&box1.x);
@@ -28,6 +40,13 @@ impl AABB {
         let z = box0.z.merge(&box1.z);
         Self { x, y, z }
     }
+    // The box shared by both boxes.
+    pub fn overlap(box0: &AABB, box1: &AABB) -> Self {
+        let x = box0.x.intersect(&box1.x);
+        let y = box0.y.intersect(&box1.y);
+        let z = box0.z.intersect(&box1.z);
+        Self { x, y, z }
+    }
     pub fn axis_interval(&self, n: usize) -> Interval {
         match n {
             0 => self.x,
@@ -36,6 +55,12 @@ impl AABB {
             _ => panic!("Invalid axis index"),
         }
     }
+    // Whether the box extends infinitely along any axis, as for unbounded objects like planes.
+    pub fn is_infinite(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .any(|axis| axis.size() == f64::INFINITY)
+    }
     pub fn longest_axis(&self) -> usize {
         let x_size = self.x.size();
         let y_size = self.y.size();
@@ -53,35 +78,307 @@ impl AABB {
             2
         }
     }
-    pub fn hit(&self, r: &Ray, mut ray_t: Interval) -> bool {
+    // Slab test that never misses a box the ray actually touches.
+    // Axis-parallel rays (including -0.0 components) produce infinite or NaN slab distances,
+    // NaNs come from 0 * inf when the origin lies exactly on a slab plane.
+    // Comparisons against NaN are false, so such an axis simply doesn't narrow `ray_t`.
+    pub fn hit(&self, r: &Ray, ray_t: Interval) -> bool {
+        self.hit_interval(r, ray_t).is_some()
+    }
+
+    // Same as hit, but returns the part of ray_t that lies inside the box.
+    pub fn hit_interval(&self, r: &Ray, mut ray_t: Interval) -> Option<Interval> {
         let ray_origin: Point3 = r.origin();
         let ray_dir = r.direction();
 
         for i in 0..3 {
             let axis = self.axis_interval(i);
-            // this can be NaN if the ray_dir component is 0
             let adinv = 1.0 / ray_dir.get(i);
 
-            let mut t0 = (axis.start - ray_origin.get(i)) * adinv;
-            let mut t1 = (axis.end - ray_origin.get(i)) * adinv;
+            // pick the near/far planes from the sign of the direction, so that no swap is needed
+            let (near, far) = if adinv.is_sign_negative() {
+                (axis.end, axis.start)
+            } else {
+                (axis.start, axis.end)
+            };
+            let t0 = (near - ray_origin.get(i)) * adinv;
+            // widen the far distance to cover the rounding error of the computation above
+            let t1 = (far - ray_origin.get(i)) * adinv * ROUNDING_PAD;
 
-            if t0 > t1 {
-                std::mem::swap(&mut t0, &mut t1);
+            if t0 > ray_t.start {
+                ray_t.start = t0;
+            }
+            if t1 < ray_t.end {
+                ray_t.end = t1;
             }
 
-            ray_t.start = ray_t.start.max(t0);
-            ray_t.end = ray_t.end.min(t1);
+            if ray_t.start > ray_t.end {
+                return None;
+            }
+        }
+        Some(ray_t)
+    }
 
-            if ray_t.start >= ray_t.end {
-                return false;
+    // Makes sure no side of the box is thinner than MIN_PADDING,
+    // so that planar objects still have a box that rays can enter.
+    fn pad_to_minimums(self) -> Self {
+        let pad = |interval: Interval| {
+            if interval.size() < MIN_PADDING {
+                interval.expand(MIN_PADDING / 2.0)
+            } else {
+                interval
             }
+        };
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
         }
-        true
     }
 }
 
+// Minimum thickness of a box along any axis.
+const MIN_PADDING: f64 = 0.0001;
+
+// 1 + 2 * gamma(3), where gamma(n) = n * eps / (1 - n * eps) bounds the relative error
+// of n floating point operations.
+pub(crate) const ROUNDING_PAD: f64 =
+    1.0 + 2.0 * (3.0 * f64::EPSILON * 0.5) / (1.0 - 3.0 * f64::EPSILON * 0.5);
+
 pub static EMPTY_AABB: AABB = AABB {
     x: EMPTY_INTERVAL,
     y: EMPTY_INTERVAL,
     z: EMPTY_INTERVAL,
 };
+pub static UNIVERSE_AABB: AABB = AABB {
+    x: UNIVERSE_INTERVAL,
+    y: UNIVERSE_INTERVAL,
+    z: UNIVERSE_INTERVAL,
+};
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::hittable::wide_bvh::{AABBLanes, RayLanes, BVH_WIDTH};
+
+    const ALL_T: Interval = Interval {
+        start: 0.0,
+        end: f64::INFINITY,
+    };
+
+    fn random_point(rng: &mut StdRng, range: f64) -> Point3 {
+        Point3::new(
+            rng.gen_range(-range..range),
+            rng.gen_range(-range..range),
+            rng.gen_range(-range..range),
+        )
+    }
+
+    fn random_box(rng: &mut StdRng) -> AABB {
+        AABB::new_from_points(random_point(rng, 10.0), random_point(rng, 10.0))
+    }
+
+    fn contains(bbox: &AABB, p: &Point3) -> bool {
+        (0..3).all(|i| bbox.axis_interval(i).contains(p.get(i)))
+    }
+
+    fn random_inside(rng: &mut StdRng, bbox: &AABB) -> Point3 {
+        let mut coordinate = |i| {
+            let axis = bbox.axis_interval(i);
+            axis.start + rng.gen::<f64>() * axis.size()
+        };
+        Point3::new(coordinate(0), coordinate(1), coordinate(2))
+    }
+
+    fn random_outside(rng: &mut StdRng, bbox: &AABB) -> Point3 {
+        loop {
+            let p = random_point(rng, 20.0);
+            if !contains(bbox, &p) {
+                return p;
+            }
+        }
+    }
+
+    #[test]
+    fn rays_towards_an_inside_point_hit() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10_000 {
+            let bbox = random_box(&mut rng);
+            let target = random_inside(&mut rng, &bbox);
+            let origin = random_outside(&mut rng, &bbox);
+            let r = Ray::new(origin, target - origin, 0.0);
+            let inside = bbox.hit_interval(&r, ALL_T);
+            assert!(inside.is_some(), "{bbox:?} missed by {r:?}");
+            // the target is reached at t = 1
+            assert!(inside.unwrap().contains(1.0), "{bbox:?} {r:?} {inside:?}");
+        }
+    }
+
+    #[test]
+    fn rays_leaving_a_box_behind_miss() {
+        // the box is convex, so going away from an inside point from outside never enters it
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..10_000 {
+            let bbox = random_box(&mut rng);
+            let target = random_inside(&mut rng, &bbox);
+            let origin = random_outside(&mut rng, &bbox);
+            let r = Ray::new(origin, origin - target, 0.0);
+            assert!(!bbox.hit(&r, ALL_T), "{bbox:?} hit by {r:?}");
+        }
+    }
+
+    #[test]
+    fn axis_parallel_rays() {
+        let bbox = AABB::new_from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        for axis in 0..3 {
+            for sign in [1.0, -1.0] {
+                for zero in [0.0, -0.0] {
+                    let direction = |along: f64| {
+                        let mut d = [zero; 3];
+                        d[axis] = along;
+                        Vec3::new(d[0], d[1], d[2])
+                    };
+                    let from = |offset: f64, across: f64| {
+                        let mut o = [across; 3];
+                        o[axis] = offset;
+                        Point3::new(o[0], o[1], o[2])
+                    };
+                    // through the box, from either side
+                    let r = Ray::new(from(-5.0 * sign, 0.5), direction(sign), 0.0);
+                    assert!(bbox.hit(&r, ALL_T), "{r:?}");
+                    // beside the box
+                    let r = Ray::new(from(-5.0 * sign, 1.5), direction(sign), 0.0);
+                    assert!(!bbox.hit(&r, ALL_T), "{r:?}");
+                    // pointing away
+                    let r = Ray::new(from(-5.0 * sign, 0.5), direction(-sign), 0.0);
+                    assert!(!bbox.hit(&r, ALL_T), "{r:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_zero_components_match_positive_zero() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..10_000 {
+            let bbox = random_box(&mut rng);
+            let origin = random_point(&mut rng, 20.0);
+            let d = random_point(&mut rng, 1.0);
+            let axis = rng.gen_range(0..3);
+            let with_zero = |zero: f64| {
+                let mut c = [d.x, d.y, d.z];
+                c[axis] = zero;
+                Ray::new(origin, Vec3::new(c[0], c[1], c[2]), 0.0)
+            };
+            assert_eq!(
+                bbox.hit_interval(&with_zero(0.0), ALL_T),
+                bbox.hit_interval(&with_zero(-0.0), ALL_T),
+                "{bbox:?} {origin:?} {d:?} {axis}"
+            );
+        }
+    }
+
+    #[test]
+    fn origins_on_a_slab_plane() {
+        let bbox = AABB::new_from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        for plane in [-1.0, 1.0] {
+            // sliding along the face: 0 * inf gives NaN for that axis, which must not reject the box
+            for zero in [0.0, -0.0] {
+                let r = Ray::new(
+                    Point3::new(plane, -5.0, 0.0),
+                    Vec3::new(zero, 1.0, 0.0),
+                    0.0,
+                );
+                assert!(bbox.hit(&r, ALL_T), "{r:?}");
+            }
+            // crossing the face
+            let r = Ray::new(
+                Point3::new(plane, 0.0, 0.0),
+                Vec3::new(-plane, 0.0, 0.0),
+                0.0,
+            );
+            assert!(bbox.hit(&r, ALL_T), "{r:?}");
+            // leaving through the face
+            let r = Ray::new(
+                Point3::new(plane, 0.0, 0.0),
+                Vec3::new(plane, 0.0, 0.0),
+                0.0,
+            );
+            let inside = bbox.hit_interval(&r, ALL_T);
+            assert!(inside.is_none_or(|t| t.end <= 1e-12), "{r:?} {inside:?}");
+        }
+    }
+
+    #[test]
+    fn zero_width_boxes_are_padded() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..1_000 {
+            let axis = rng.gen_range(0..3);
+            let a = random_point(&mut rng, 10.0);
+            let mut b = random_point(&mut rng, 10.0);
+            match axis {
+                0 => b.x = a.x,
+                1 => b.y = a.y,
+                _ => b.z = a.z,
+            }
+            let bbox = AABB::new_from_points(a, b);
+            assert!(bbox.axis_interval(axis).size() >= MIN_PADDING * 0.999);
+
+            // a ray crossing the flat side head on
+            let target = random_inside(&mut rng, &bbox);
+            let mut d = [0.0; 3];
+            d[axis] = 1.0;
+            let direction = Vec3::new(d[0], d[1], d[2]);
+            let r = Ray::new(target - direction * 5.0, direction, 0.0);
+            assert!(bbox.hit(&r, ALL_T), "{bbox:?} {r:?}");
+        }
+    }
+
+    #[test]
+    fn empty_box_is_never_hit() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut directions = vec![
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-0.0, 0.0, -1.0),
+            Vec3::new(0.0, -0.0, 0.0),
+        ];
+        directions.extend((0..1_000).map(|_| random_point(&mut rng, 1.0)));
+        for direction in directions {
+            let origin = random_point(&mut rng, 20.0);
+            let r = Ray::new(origin, direction, 0.0);
+            assert!(!EMPTY_AABB.hit(&r, ALL_T), "{r:?}");
+            assert!(!EMPTY_AABB.hit(&r, UNIVERSE_INTERVAL), "{r:?}");
+        }
+    }
+
+    #[test]
+    fn lanes_agree_with_single_boxes() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..10_000 {
+            let count = rng.gen_range(0..=BVH_WIDTH);
+            let boxes: Vec<AABB> = (0..count).map(|_| random_box(&mut rng)).collect();
+            let lanes = AABBLanes::new(&boxes);
+
+            let origin = random_point(&mut rng, 20.0);
+            let mut d = random_point(&mut rng, 1.0);
+            // include axis-parallel rays and signed zeros
+            match rng.gen_range(0..4) {
+                0 => d.x = 0.0,
+                1 => d.y = -0.0,
+                _ => (),
+            }
+            let r = Ray::new(origin, d, 0.0);
+            let ray_t = Interval::new(rng.gen_range(0.0..1.0), rng.gen_range(1.0..50.0));
+
+            let t_enter = lanes.hit(&RayLanes::from(&r), ray_t);
+            for (lane, t) in t_enter.iter().enumerate() {
+                match boxes.get(lane).and_then(|b| b.hit_interval(&r, ray_t)) {
+                    Some(inside) => assert_eq!(*t, inside.start, "lane {lane} {r:?}"),
+                    None => assert!(t.is_infinite(), "lane {lane} {r:?}"),
+                }
+            }
+        }
+    }
+}
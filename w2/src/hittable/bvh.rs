@@ -15,9 +15,13 @@ pub struct BVHNode {
     bbox: AABB,
 }
 
+// Unbounded objects are kept, but they make every node above them unbounded as well.
+// Prefer HittableList::into_bvh for lists that may contain them.
 impl From<HittableList> for BVHNode {
     fn from(list: HittableList) -> Self {
-        Self::from(list.objects.as_slice())
+        let objects: Vec<Rc<dyn Hittable>> =
+            list.objects.into_iter().chain(list.unbounded).collect();
+        Self::from(objects.as_slice())
     }
 }
 
@@ -89,14 +93,14 @@ impl Hittable for BVHNode {
             return None;
         }
         let mut return_rec: Option<HitRecord> = None;
-        let hit_left = self.left.hit(r, ray_t);
+        let hit_left = self.left.hit_opaque(r, ray_t);
 
         if let Some(rec) = hit_left {
             ray_t.end = rec.t;
             return_rec = Some(rec);
         }
 
-        let hit_right = self.right.hit(r, ray_t);
+        let hit_right = self.right.hit_opaque(r, ray_t);
 
         if let Some(rec) = hit_right {
             return_rec = Some(rec);
@@ -105,6 +109,21 @@ impl Hittable for BVHNode {
         return_rec
     }
 
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        if !self.bbox.hit(r, ray_t) {
+            return 1.0;
+        }
+        let left = self.left.transmittance(r, ray_t);
+        if left <= 0.0 {
+            return 0.0;
+        }
+        // a single object is stored on both sides
+        if Rc::ptr_eq(&self.left, &self.right) {
+            return left;
+        }
+        left * self.right.transmittance(r, ray_t)
+    }
+
     fn bounding_box(&self) -> AABB {
         self.bbox
     }
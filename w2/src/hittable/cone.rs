@@ -0,0 +1,119 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, disk::Disk, HitRecord, Hittable};
+
+// A cone with a capped circular base at `base`, and its apex at `base + axis`.
+#[derive(Debug)]
+pub struct Cone {
+    base: Point3,
+    height: f64,
+    radius: f64,
+    frame: ONB,
+    material: Rc<dyn Material>,
+    base_cap: Disk,
+    bbox: AABB,
+}
+
+impl Cone {
+    pub fn new(base: Point3, axis: Vec3, radius: f64, material: Rc<dyn Material>) -> Self {
+        let radius = radius.max(0.0);
+        let base_cap = Disk::new(base, -axis, radius, material.clone());
+        let apex = base + axis;
+        let bbox =
+            AABB::surrounding_box(&base_cap.bounding_box(), &AABB::new_from_points(apex, apex));
+        Self {
+            base,
+            height: axis.length(),
+            radius,
+            frame: ONB::new(&axis),
+            material,
+            base_cap,
+            bbox,
+        }
+    }
+
+    fn hit_side(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // in the local frame, the side is x^2 + y^2 = (k * (height - z))^2 with 0 <= z <= height
+        let o = self.frame.world_to_local(&(r.origin() - self.base));
+        let d = self.frame.world_to_local(&r.direction());
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let hz = self.height - o.z;
+
+        let a = d.x * d.x + d.y * d.y - k2 * d.z * d.z;
+        let half_b = o.x * d.x + o.y * d.y + k2 * hz * d.z;
+        let c = o.x * o.x + o.y * o.y - k2 * hz * hz;
+
+        let roots = if a.abs() < 1e-12 {
+            // ray is parallel to the side, so it crosses the double cone only once
+            if half_b == 0.0 {
+                return None;
+            }
+            vec![-c / (2.0 * half_b)]
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let sqrtd = discriminant.sqrt();
+            let (t0, t1) = ((-half_b - sqrtd) / a, (-half_b + sqrtd) / a);
+            vec![t0.min(t1), t0.max(t1)]
+        };
+
+        roots
+            .into_iter()
+            .find(|&t| {
+                ray_t.surrounds(t) && Interval::new(0.0, self.height).contains(o.z + t * d.z)
+            })
+            .map(|t| {
+                let local = o + d * t;
+                let rho = (local.x * local.x + local.y * local.y).sqrt();
+                // the gradient of rho - k * (height - z), with the apex given an axial normal
+                let local_normal = if rho > 0.0 {
+                    Vec3::new(local.x / rho, local.y / rho, k)
+                } else {
+                    Vec3::new(0.0, 0.0, 1.0)
+                };
+                let outward_normal = self.frame.local_to_world(&local_normal).unit();
+                let u = (local.y.atan2(local.x) + PI) / (2.0 * PI);
+                let v = local.z / self.height;
+                let dpdu = Vec3::new(-local.y, local.x, 0.0) * (2.0 * PI);
+                // towards the apex, along the side
+                let dpdv = if rho > 0.0 {
+                    Vec3::new(-k * local.x / rho, -k * local.y / rho, 1.0) * self.height
+                } else {
+                    Vec3::new(0.0, 0.0, self.height)
+                };
+                HitRecord::new(r.at(t), r, outward_normal, t, u, v, self.material.clone())
+                    .with_tangents(
+                        self.frame.local_to_world(&dpdu),
+                        self.frame.local_to_world(&dpdv),
+                    )
+            })
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, mut ray_t: Interval) -> Option<HitRecord> {
+        let mut return_rec: Option<HitRecord> = None;
+        for rec in [self.hit_side(r, ray_t), self.base_cap.hit(r, ray_t)]
+            .into_iter()
+            .flatten()
+        {
+            if rec.t < ray_t.end {
+                ray_t.end = rec.t;
+                return_rec = Some(rec);
+            }
+        }
+        return_rec
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
@@ -0,0 +1,97 @@
+use std::rc::Rc;
+
+use crate::{
+    material::{isotropic::Isotropic, Material},
+    primitive::{
+        color::Color,
+        interval::{Interval, MAX_INTERVAL},
+        ray::Ray,
+    },
+    texture::Texture,
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+// A volume of constant density, bounded by any hittable.
+// Rays passing through it scatter at a random distance,
+// with probability proportional to the distance travelled inside.
+// The boundary is assumed to be convex: a ray enters and exits it at most once.
+#[derive(Debug)]
+pub struct ConstantMedium {
+    boundary: Rc<dyn Hittable>,
+    neg_inv_density: f64,
+    phase_function: Rc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Rc<dyn Hittable>, density: f64, albedo: Color) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Rc::new(Isotropic::new(albedo)),
+        }
+    }
+
+    pub fn new_textured(
+        boundary: Rc<dyn Hittable>,
+        density: f64,
+        texture: Rc<dyn Texture>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Rc::new(Isotropic::from(texture)),
+        }
+    }
+}
+
+impl ConstantMedium {
+    // The part of ray_t inside the boundary, if any.
+    fn inside(&self, r: &Ray, ray_t: Interval) -> Option<Interval> {
+        // find where the ray enters and exits the boundary, regardless of ray_t
+        let rec1 = self.boundary.hit(r, MAX_INTERVAL)?;
+        let rec2 = self
+            .boundary
+            .hit(r, Interval::new(rec1.t + 0.0001, f64::INFINITY))?;
+
+        let start = rec1.t.max(ray_t.start).max(0.0);
+        let end = rec2.t.min(ray_t.end);
+        if start >= end {
+            return None;
+        }
+        Some(Interval::new(start, end))
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let inside = self.inside(r, ray_t)?;
+
+        let ray_length = r.direction().length();
+        let distance_inside_boundary = inside.size() * ray_length;
+        let hit_distance = self.neg_inv_density * rand::random::<f64>().ln();
+
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = inside.start + hit_distance / ray_length;
+        Some(HitRecord::in_medium(
+            r.at(t),
+            t,
+            self.phase_function.clone(),
+        ))
+    }
+
+    // Beer-Lambert law, exact for a constant density.
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        match self.inside(r, ray_t) {
+            Some(inside) => (inside.size() * r.direction().length() / self.neg_inv_density).exp(),
+            None => 1.0,
+        }
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,242 @@
+use std::rc::Rc;
+
+use crate::primitive::{
+    interval::{Interval, MAX_INTERVAL},
+    ray::Ray,
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference, // left minus right
+}
+
+impl CsgOperation {
+    fn inside(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// Constructive solid geometry of two closed hittables.
+// The ray is classified against both operands along its whole length,
+// and the combined surface lies wherever the ray switches between inside and outside the result.
+#[derive(Debug)]
+pub struct Csg {
+    left: Rc<dyn Hittable>,
+    right: Rc<dyn Hittable>,
+    operation: CsgOperation,
+    bbox: AABB,
+}
+
+impl Csg {
+    pub fn new(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>, operation: CsgOperation) -> Self {
+        let bbox = match operation {
+            CsgOperation::Union => {
+                AABB::surrounding_box(&left.bounding_box(), &right.bounding_box())
+            }
+            CsgOperation::Intersection => {
+                AABB::overlap(&left.bounding_box(), &right.bounding_box())
+            }
+            CsgOperation::Difference => left.bounding_box(),
+        };
+        Self {
+            left,
+            right,
+            operation,
+            bbox,
+        }
+    }
+
+    pub fn union(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self::new(left, right, CsgOperation::Union)
+    }
+
+    pub fn intersection(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self::new(left, right, CsgOperation::Intersection)
+    }
+
+    pub fn difference(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Self {
+        Self::new(left, right, CsgOperation::Difference)
+    }
+
+    // Walks through the crossings of both operands in order,
+    // keeping those where the ray enters or leaves the combined solid.
+    fn crossings(&self, r: &Ray, ray_t: Interval) -> Vec<HitRecord> {
+        if !self.bbox.hit(r, ray_t) {
+            return Vec::new();
+        }
+
+        // The whole line is needed, since crossings before ray_t decide
+        // whether the ray starts out inside the operands.
+        let mut left = self.left.hit_all(r, MAX_INTERVAL).into_iter().peekable();
+        let mut right = self.right.hit_all(r, MAX_INTERVAL).into_iter().peekable();
+
+        let mut in_left = false;
+        let mut in_right = false;
+        let mut records = Vec::new();
+
+        loop {
+            let from_left = match (left.peek(), right.peek()) {
+                (None, None) => break,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (Some(a), Some(b)) => a.t <= b.t,
+            };
+            let was_inside = self.operation.inside(in_left, in_right);
+            let mut rec = if from_left {
+                let rec = left.next().unwrap();
+                in_left = rec.front_face;
+                rec
+            } else {
+                let rec = right.next().unwrap();
+                in_right = rec.front_face;
+                rec
+            };
+
+            if was_inside == self.operation.inside(in_left, in_right) || !ray_t.surrounds(rec.t) {
+                continue;
+            }
+            // the subtracted solid's surface faces the other way in the result
+            if !from_left && self.operation == CsgOperation::Difference {
+                rec.front_face = !rec.front_face;
+            }
+            records.push(rec);
+        }
+        records
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        self.crossings(r, ray_t).into_iter().next()
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+
+    fn hit_all(&self, r: &Ray, ray_t: Interval) -> Vec<HitRecord> {
+        self.crossings(r, ray_t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::sphere::Sphere,
+        material::lambertian::Lambertian,
+        primitive::{color::Color, point3::Point3, vec3::Vec3},
+    };
+
+    fn sphere(x: f64, radius: f64) -> Rc<dyn Hittable> {
+        Rc::new(Sphere::new(
+            Vec3::new(x, 0.0, 0.0),
+            radius,
+            Rc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+        ))
+    }
+
+    // Two unit spheres overlapping between x = -0.5 and x = 0.5.
+    fn operands() -> (Rc<dyn Hittable>, Rc<dyn Hittable>) {
+        (sphere(-0.5, 1.0), sphere(0.5, 1.0))
+    }
+
+    // Crossings of a ray along the x axis starting at x, as (x, front_face) pairs.
+    fn crossings_from(csg: &Csg, x: f64) -> Vec<(f64, bool)> {
+        let r = Ray::new(Point3::new(x, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        csg.hit_all(&r, Interval::new(0.001, f64::INFINITY))
+            .into_iter()
+            .map(|rec| (r.at(rec.t).x, rec.front_face))
+            .collect()
+    }
+
+    fn assert_crossings(crossings: &[(f64, bool)], expected: &[(f64, bool)]) {
+        assert_eq!(crossings.len(), expected.len(), "{crossings:?}");
+        for ((x, front), (want_x, want_front)) in crossings.iter().zip(expected) {
+            assert!((x - want_x).abs() < 1e-9, "{crossings:?}");
+            assert_eq!(front, want_front, "{crossings:?}");
+        }
+    }
+
+    #[test]
+    fn union() {
+        let (a, b) = operands();
+        let csg = Csg::union(a, b);
+        assert_crossings(&crossings_from(&csg, -5.0), &[(-1.5, true), (1.5, false)]);
+    }
+
+    #[test]
+    fn intersection() {
+        let (a, b) = operands();
+        let csg = Csg::intersection(a, b);
+        assert_crossings(&crossings_from(&csg, -5.0), &[(-0.5, true), (0.5, false)]);
+    }
+
+    #[test]
+    fn difference_flips_the_subtracted_surface() {
+        let (a, b) = operands();
+        let csg = Csg::difference(a, b);
+        // the ray leaves the result where it enters b
+        assert_crossings(&crossings_from(&csg, -5.0), &[(-1.5, true), (-0.5, false)]);
+
+        // from the other side, the ray enters the result where it leaves b
+        let r = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), 0.0);
+        let recs = csg.hit_all(&r, Interval::new(0.001, f64::INFINITY));
+        assert_eq!(recs.len(), 2);
+        assert!((r.at(recs[0].t).x + 0.5).abs() < 1e-9);
+        assert!(recs[0].front_face);
+        assert!((r.at(recs[1].t).x + 1.5).abs() < 1e-9);
+        assert!(!recs[1].front_face);
+    }
+
+    #[test]
+    fn hit_returns_the_first_crossing_in_t() {
+        let (a, b) = operands();
+        let csg = Csg::intersection(a, b);
+        // twice the unit direction, so that t is half the distance travelled
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0), 0.0);
+        let rec = csg.hit(&r, Interval::new(0.001, f64::INFINITY)).unwrap();
+        assert!((rec.t - 2.25).abs() < 1e-9, "{}", rec.t);
+        assert!(csg.hit(&r, Interval::new(0.001, 2.0)).is_none());
+        // a range starting between the crossings still sees the ray as inside
+        let rec = csg.hit(&r, Interval::new(2.5, f64::INFINITY)).unwrap();
+        assert!((rec.t - 2.75).abs() < 1e-9, "{}", rec.t);
+        assert!(!rec.front_face);
+    }
+
+    #[test]
+    fn nested() {
+        let (a, b) = operands();
+        // the union, with a bite taken out of its right end
+        let csg = Csg::difference(Rc::new(Csg::union(a, b)), sphere(1.5, 0.5));
+        assert_crossings(&crossings_from(&csg, -5.0), &[(-1.5, true), (1.0, false)]);
+
+        // and intersected again, which keeps the flipped face of the inner difference
+        let outer = Csg::intersection(Rc::new(csg), sphere(0.5, 1.0));
+        assert_crossings(&crossings_from(&outer, -5.0), &[(-0.5, true), (1.0, false)]);
+    }
+
+    #[test]
+    fn ray_starting_inside_an_operand() {
+        let (a, b) = operands();
+        let union = Csg::union(a.clone(), b.clone());
+        assert_crossings(&crossings_from(&union, 0.0), &[(1.5, false)]);
+
+        // inside a only, so inside the difference until the ray enters b
+        let difference = Csg::difference(a.clone(), b.clone());
+        assert_crossings(&crossings_from(&difference, -1.0), &[(-0.5, false)]);
+        // inside both, so outside the difference the whole way
+        assert_crossings(&crossings_from(&difference, 0.0), &[]);
+
+        let intersection = Csg::intersection(a, b);
+        assert_crossings(&crossings_from(&intersection, 0.0), &[(0.5, false)]);
+    }
+}
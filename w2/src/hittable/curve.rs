@@ -0,0 +1,233 @@
+use std::rc::Rc;
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+// A thin tube following a cubic Bézier curve, with its width varying linearly along the curve.
+// Meant for hair, fur, grass and other fibers, which are too thin to be worth modelling as
+// actual geometry: the curve is hit like a flat ribbon facing the ray,
+// and the shading normal is bent across the ribbon so that it shades like a round tube.
+#[derive(Debug)]
+pub struct Curve {
+    control_points: [Point3; 4],
+    widths: (f64, f64),  // width at the start and the end of the curve
+    u_range: (f64, f64), // range of the whole strand covered by this segment
+    max_depth: u32,
+    material: Rc<dyn Material>,
+    bbox: AABB,
+}
+
+impl Curve {
+    pub fn new(
+        control_points: [Point3; 4],
+        start_width: f64,
+        end_width: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let [p0, p1, p2, p3] = control_points;
+        // a Bézier curve lies in the convex hull of its control points
+        let half_width = 0.5 * start_width.max(end_width);
+        let pad = Vec3::new(half_width, half_width, half_width);
+        let bbox = [p1, p2, p3]
+            .iter()
+            .fold(AABB::new_from_points(p0 - pad, p0 + pad), |bbox, &p| {
+                AABB::surrounding_box(&bbox, &AABB::new_from_points(p - pad, p + pad))
+            });
+
+        // Subdivide until the segments are within a fraction of the width from straight lines,
+        // which depends on how much the curve bends (Nakamaru and Ohno).
+        let bend = [p0 - p1 * 2.0 + p2, p1 - p2 * 2.0 + p3]
+            .iter()
+            .map(|d| d.x.abs().max(d.y.abs()).max(d.z.abs()))
+            .fold(0.0, f64::max);
+        let epsilon = start_width.max(end_width) / 20.0;
+        let max_depth = if bend > 0.0 && epsilon > 0.0 {
+            ((std::f64::consts::SQRT_2 * 6.0 * bend / (8.0 * epsilon)).log2() / 2.0)
+                .ceil()
+                .clamp(0.0, 10.0) as u32
+        } else {
+            0
+        };
+
+        Self {
+            control_points,
+            widths: (start_width, end_width),
+            u_range: (0.0, 1.0),
+            max_depth,
+            material,
+            bbox,
+        }
+    }
+
+    // Splits a strand made of consecutive cubic segments into curves.
+    // points holds 3n + 1 control points, where each segment shares its last point with the next.
+    // The width varies linearly along the whole strand, and u runs from 0 at the root to 1 at the tip.
+    pub fn strand(
+        points: &[Point3],
+        root_width: f64,
+        tip_width: f64,
+        material: Rc<dyn Material>,
+    ) -> Vec<Rc<dyn Hittable>> {
+        let segments = points.len().saturating_sub(1) / 3;
+        (0..segments)
+            .map(|i| {
+                let u0 = i as f64 / segments as f64;
+                let u1 = (i + 1) as f64 / segments as f64;
+                let width = |u: f64| root_width + (tip_width - root_width) * u;
+                let mut curve = Curve::new(
+                    [
+                        points[3 * i],
+                        points[3 * i + 1],
+                        points[3 * i + 2],
+                        points[3 * i + 3],
+                    ],
+                    width(u0),
+                    width(u1),
+                    material.clone(),
+                );
+                curve.u_range = (u0, u1);
+                Rc::new(curve) as Rc<dyn Hittable>
+            })
+            .collect()
+    }
+
+    fn eval(cp: &[Point3; 4], u: f64) -> (Point3, Vec3) {
+        let s = 1.0 - u;
+        let point = cp[0] * (s * s * s)
+            + cp[1] * (3.0 * s * s * u)
+            + cp[2] * (3.0 * s * u * u)
+            + cp[3] * (u * u * u);
+        let tangent = (cp[1] - cp[0]) * (3.0 * s * s)
+            + (cp[2] - cp[1]) * (6.0 * s * u)
+            + (cp[3] - cp[2]) * (3.0 * u * u);
+        (point, tangent)
+    }
+
+    // de Casteljau split at the middle
+    fn split(cp: &[Point3; 4]) -> ([Point3; 4], [Point3; 4]) {
+        let mid = |a: Point3, b: Point3| (a + b) * 0.5;
+        let p01 = mid(cp[0], cp[1]);
+        let p12 = mid(cp[1], cp[2]);
+        let p23 = mid(cp[2], cp[3]);
+        let p012 = mid(p01, p12);
+        let p123 = mid(p12, p23);
+        let center = mid(p012, p123);
+        ([cp[0], p01, p012, center], [center, p123, p23, cp[3]])
+    }
+
+    fn width(&self, u: f64) -> f64 {
+        self.widths.0 + (self.widths.1 - self.widths.0) * u
+    }
+
+    // Recursively searches the segment between u0 and u1,
+    // with cp given in the ray's frame: the ray starts at the origin and runs along +z.
+    // Returns the curve parameter and the distance along the ray of the closest hit.
+    fn hit_segment(
+        &self,
+        cp: &[Point3; 4],
+        u0: f64,
+        u1: f64,
+        depth: u32,
+        z_range: Interval,
+    ) -> Option<(f64, f64)> {
+        let half_width = 0.5 * self.width(u0).max(self.width(u1));
+        let (lo, hi) = cp.iter().fold(
+            (
+                Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+                Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(lo, hi), p| {
+                (
+                    Point3::new(lo.x.min(p.x), lo.y.min(p.y), lo.z.min(p.z)),
+                    Point3::new(hi.x.max(p.x), hi.y.max(p.y), hi.z.max(p.z)),
+                )
+            },
+        );
+        if lo.x - half_width > 0.0
+            || hi.x + half_width < 0.0
+            || lo.y - half_width > 0.0
+            || hi.y + half_width < 0.0
+            || lo.z - half_width > z_range.end
+            || hi.z + half_width < z_range.start
+        {
+            return None;
+        }
+
+        if depth > 0 {
+            let (first, second) = Self::split(cp);
+            let mid = 0.5 * (u0 + u1);
+            let near = self.hit_segment(&first, u0, mid, depth - 1, z_range);
+            let z_range = near.map_or(z_range, |(_, z)| Interval::new(z_range.start, z));
+            let far = self.hit_segment(&second, mid, u1, depth - 1, z_range);
+            return far.or(near);
+        }
+
+        // Treat the segment as a straight line, and find the point on it closest to the ray.
+        let (a, b) = (cp[0], cp[3]);
+        let ab = Vec3::new(b.x - a.x, b.y - a.y, 0.0);
+        let len_squared = ab.length_squared();
+        let w = if len_squared > 0.0 {
+            (-(a.x * ab.x + a.y * ab.y) / len_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let u = u0 + (u1 - u0) * w;
+        let (p, _) = Self::eval(cp, w);
+        let half_width = 0.5 * self.width(u);
+        if p.x * p.x + p.y * p.y > half_width * half_width || !z_range.surrounds(p.z) {
+            return None;
+        }
+        Some((u, p.z))
+    }
+}
+
+impl Hittable for Curve {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let dir_len = r.direction().length();
+        let frame = ONB::new(&r.direction());
+        let to_ray_space = |p: &Point3| frame.world_to_local(&(*p - r.origin()));
+        let cp = self.control_points.map(|p| to_ray_space(&p));
+
+        let z_range = Interval::new(ray_t.start * dir_len, ray_t.end * dir_len);
+        let (u, z) = self.hit_segment(&cp, 0.0, 1.0, self.max_depth, z_range)?;
+        let t = z / dir_len;
+        let p = r.at(t);
+
+        // bend the normal across the ribbon, as if it were the surface of a tube
+        let (center, tangent) = Self::eval(&self.control_points, u);
+        let tangent = if tangent.near_zero() {
+            self.control_points[3] - self.control_points[0]
+        } else {
+            tangent
+        };
+        let side = tangent.cross(&r.direction()).unit();
+        let facing = tangent.cross(&side).unit(); // the ribbon faces back along the ray
+        let half_width = 0.5 * self.width(u);
+        let offset = ((p - center).dot(&side) / half_width).clamp(-1.0, 1.0);
+        let tube_normal = facing * (1.0 - offset * offset).sqrt() + side * offset;
+
+        let u_scale = self.u_range.1 - self.u_range.0;
+        let strand_u = self.u_range.0 + u_scale * u;
+        Some(
+            HitRecord::new(
+                p,
+                r,
+                facing,
+                t,
+                strand_u,
+                0.5 * (offset + 1.0),
+                self.material.clone(),
+            )
+            .with_tangents(tangent / u_scale, side * (2.0 * half_width))
+            .with_shading_normal(tube_normal),
+        )
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
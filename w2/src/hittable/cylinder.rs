@@ -0,0 +1,99 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, disk::Disk, HitRecord, Hittable};
+
+// A capped cylinder, spanning from `base` to `base + axis`.
+#[derive(Debug)]
+pub struct Cylinder {
+    base: Point3,
+    height: f64,
+    radius: f64,
+    frame: ONB,
+    material: Rc<dyn Material>,
+    bottom_cap: Disk,
+    top_cap: Disk,
+    bbox: AABB,
+}
+
+impl Cylinder {
+    pub fn new(base: Point3, axis: Vec3, radius: f64, material: Rc<dyn Material>) -> Self {
+        let radius = radius.max(0.0);
+        let bottom_cap = Disk::new(base, -axis, radius, material.clone());
+        let top_cap = Disk::new(base + axis, axis, radius, material.clone());
+        let bbox = AABB::surrounding_box(&bottom_cap.bounding_box(), &top_cap.bounding_box());
+        Self {
+            base,
+            height: axis.length(),
+            radius,
+            frame: ONB::new(&axis),
+            material,
+            bottom_cap,
+            top_cap,
+            bbox,
+        }
+    }
+
+    fn hit_side(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // in the local frame, the cylinder is x^2 + y^2 = radius^2 with 0 <= z <= height
+        let o = self.frame.world_to_local(&(r.origin() - self.base));
+        let d = self.frame.world_to_local(&r.direction());
+
+        let a = d.x * d.x + d.y * d.y;
+        if a == 0.0 {
+            return None; // ray is parallel to the axis
+        }
+        let half_b = o.x * d.x + o.y * d.y;
+        let c = o.x * o.x + o.y * o.y - self.radius * self.radius;
+
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+
+        [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            .into_iter()
+            .find(|&t| {
+                ray_t.surrounds(t) && Interval::new(0.0, self.height).contains(o.z + t * d.z)
+            })
+            .map(|t| {
+                let local = o + d * t;
+                let outward_normal =
+                    self.frame.local_to_world(&Vec3::new(local.x, local.y, 0.0)) / self.radius;
+                let u = (local.y.atan2(local.x) + PI) / (2.0 * PI);
+                let v = local.z / self.height;
+                let dpdu = Vec3::new(-local.y, local.x, 0.0) * (2.0 * PI);
+                HitRecord::new(r.at(t), r, outward_normal, t, u, v, self.material.clone())
+                    .with_tangents(self.frame.local_to_world(&dpdu), self.frame.w * self.height)
+            })
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, mut ray_t: Interval) -> Option<HitRecord> {
+        let mut return_rec: Option<HitRecord> = None;
+        for rec in [
+            self.hit_side(r, ray_t),
+            self.bottom_cap.hit(r, ray_t),
+            self.top_cap.hit(r, ray_t),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if rec.t < ray_t.end {
+                ray_t.end = rec.t;
+                return_rec = Some(rec);
+            }
+        }
+        return_rec
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
@@ -0,0 +1,206 @@
+use std::{fs, path::Path};
+
+use thiserror::Error;
+
+use crate::{
+    primitive::{interval::Interval, point3::Point3},
+    texture::perlin::Perlin,
+};
+
+use super::aabb::AABB;
+
+#[derive(Debug, Error)]
+pub enum DensityGridError {
+    #[error("failed to read density grid: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid density grid: {0}")]
+    InvalidFormat(String),
+}
+
+// A dense 3D grid of density values, covering the unit cube [0,1]^3.
+// Values are stored x-fastest, then y, then z.
+#[derive(Debug, Clone)]
+pub struct DensityGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    data: Vec<f64>,
+    max_density: f64,
+}
+
+impl DensityGrid {
+    pub fn new(nx: usize, ny: usize, nz: usize, data: Vec<f64>) -> Result<Self, DensityGridError> {
+        if nx == 0 || ny == 0 || nz == 0 {
+            return Err(DensityGridError::InvalidFormat(format!(
+                "resolution {}x{}x{} is empty",
+                nx, ny, nz
+            )));
+        }
+        // the resolution may come from a file header, so it can be absurdly large
+        let count = nx
+            .checked_mul(ny)
+            .and_then(|n| n.checked_mul(nz))
+            .ok_or_else(|| {
+                DensityGridError::InvalidFormat(format!(
+                    "resolution {}x{}x{} is too large",
+                    nx, ny, nz
+                ))
+            })?;
+        if data.len() != count {
+            return Err(DensityGridError::InvalidFormat(format!(
+                "expected {} values for resolution {}x{}x{}, got {}",
+                count,
+                nx,
+                ny,
+                nz,
+                data.len()
+            )));
+        }
+        // negative densities are meaningless, clamp them so that tracking stays unbiased
+        let data: Vec<f64> = data.into_iter().map(|d| d.max(0.0)).collect();
+        let max_density = data.iter().cloned().fold(0.0, f64::max);
+        Ok(Self {
+            nx,
+            ny,
+            nz,
+            data,
+            max_density,
+        })
+    }
+
+    // Reads headerless little-endian f32 values of the given resolution.
+    pub fn load_raw(
+        path: impl AsRef<Path>,
+        nx: usize,
+        ny: usize,
+        nz: usize,
+    ) -> Result<Self, DensityGridError> {
+        let bytes = fs::read(path)?;
+        Self::new(nx, ny, nz, Self::read_f32s(&bytes))
+    }
+
+    // Reads a Mitsuba `.vol` file holding dense float32 data.
+    // Returns the grid along with the bounding box stored in the file.
+    // Only the first channel of multi-channel volumes is used.
+    pub fn load_vol(path: impl AsRef<Path>) -> Result<(Self, AABB), DensityGridError> {
+        const HEADER_LEN: usize = 48;
+
+        let bytes = fs::read(path)?;
+        if bytes.len() < HEADER_LEN || &bytes[0..3] != b"VOL" || bytes[3] != 3 {
+            return Err(DensityGridError::InvalidFormat(
+                "missing VOL version 3 header".to_string(),
+            ));
+        }
+        let read_i32 =
+            |offset: usize| i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let encoding = read_i32(4);
+        if encoding != 1 {
+            return Err(DensityGridError::InvalidFormat(format!(
+                "unsupported encoding {}, only float32 (1) is supported",
+                encoding
+            )));
+        }
+        let dims: Vec<usize> = [8, 12, 16, 20]
+            .iter()
+            .map(|&offset| read_i32(offset).max(0) as usize)
+            .collect();
+        let (nx, ny, nz, channels) = (dims[0], dims[1], dims[2], dims[3].max(1));
+
+        let b = Self::read_f32s(&bytes[24..HEADER_LEN]);
+        let bounds =
+            AABB::new_from_points(Point3::new(b[0], b[1], b[2]), Point3::new(b[3], b[4], b[5]));
+
+        let data: Vec<f64> = Self::read_f32s(&bytes[HEADER_LEN..])
+            .into_iter()
+            .step_by(channels)
+            .collect();
+        Ok((Self::new(nx, ny, nz, data)?, bounds))
+    }
+
+    // Generates a cloud-like grid from Perlin turbulence.
+    // `scale` is the noise frequency over the unit cube,
+    // and densities fade out towards the sides of the cube so the volume has soft edges.
+    // The same seed gives the same cloud.
+    pub fn from_turbulence(resolution: usize, scale: f64, depth: i32, seed: u64) -> Self {
+        let noise = Perlin::with_seed(seed);
+        let inv_res = 1.0 / resolution as f64;
+        let mut data = Vec::with_capacity(resolution * resolution * resolution);
+        for k in 0..resolution {
+            for j in 0..resolution {
+                for i in 0..resolution {
+                    let p = Point3::new(
+                        (i as f64 + 0.5) * inv_res,
+                        (j as f64 + 0.5) * inv_res,
+                        (k as f64 + 0.5) * inv_res,
+                    );
+                    let falloff = 1.0 - ((p - Point3::new(0.5, 0.5, 0.5)).length() * 2.0);
+                    let density = noise.turbulence(&(p * scale), depth) + falloff - 0.5;
+                    data.push(density.max(0.0));
+                }
+            }
+        }
+        Self::new(resolution, resolution, resolution, data).expect("resolution must be non-zero")
+    }
+
+    pub fn max_density(&self) -> f64 {
+        self.max_density
+    }
+
+    // Trilinearly interpolated density at a point of the unit cube.
+    // Values are located at voxel centers, and clamp to the border outside of the cube.
+    pub fn density(&self, p: &Point3) -> f64 {
+        let (i0, i1, u) = Self::lerp_coords(p.x, self.nx);
+        let (j0, j1, v) = Self::lerp_coords(p.y, self.ny);
+        let (k0, k1, w) = Self::lerp_coords(p.z, self.nz);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let at = |i: usize, j: usize, k: usize| self.data[(k * self.ny + j) * self.nx + i];
+
+        lerp(
+            lerp(
+                lerp(at(i0, j0, k0), at(i1, j0, k0), u),
+                lerp(at(i0, j1, k0), at(i1, j1, k0), u),
+                v,
+            ),
+            lerp(
+                lerp(at(i0, j0, k1), at(i1, j0, k1), u),
+                lerp(at(i0, j1, k1), at(i1, j1, k1), u),
+                v,
+            ),
+            w,
+        )
+    }
+
+    // returns the two neighbouring voxel indices and the interpolation weight between them
+    fn lerp_coords(x: f64, n: usize) -> (usize, usize, f64) {
+        let x = Interval::new(0.0, (n - 1) as f64).clamp(x * n as f64 - 0.5);
+        let i0 = x.floor() as usize;
+        let i1 = (i0 + 1).min(n - 1);
+        (i0, i1, x - i0 as f64)
+    }
+
+    fn read_f32s(bytes: &[u8]) -> Vec<f64> {
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()) as f64)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflowing_resolution_is_invalid() {
+        let huge = usize::MAX / 2;
+        assert!(matches!(
+            DensityGrid::new(huge, huge, 3, vec![1.0]),
+            Err(DensityGridError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            DensityGrid::new(2, 2, 2, vec![1.0; 7]),
+            Err(DensityGridError::InvalidFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+// A flat disk facing `normal`, optionally with a hole in the middle (an annulus).
+#[derive(Debug)]
+pub struct Disk {
+    center: Point3,
+    frame: ONB,
+    inner_radius: f64,
+    outer_radius: f64,
+    material: Rc<dyn Material>,
+    bbox: AABB,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f64, material: Rc<dyn Material>) -> Self {
+        Self::new_annulus(center, normal, 0.0, radius, material)
+    }
+
+    pub fn new_annulus(
+        center: Point3,
+        normal: Vec3,
+        inner_radius: f64,
+        outer_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let outer_radius = outer_radius.max(0.0);
+        Self {
+            center,
+            frame: ONB::new(&normal),
+            inner_radius: inner_radius.clamp(0.0, outer_radius),
+            outer_radius,
+            material,
+            bbox: AABB::new_from_disk(center, &normal, outer_radius),
+        }
+    }
+
+    // returns (u, v) coordinates in range ([0,1], [0,1])
+    // u goes around the disk, v goes from the inner to the outer radius.
+    pub(super) fn polar_uv(x: f64, y: f64, inner_radius: f64, outer_radius: f64) -> (f64, f64) {
+        let rho = (x * x + y * y).sqrt();
+        let phi = y.atan2(x) + PI;
+        let width = outer_radius - inner_radius;
+        (
+            phi / (2.0 * PI),
+            if width > 0.0 {
+                (rho - inner_radius) / width
+            } else {
+                0.0
+            },
+        )
+    }
+
+    // dp/du and dp/dv of polar_uv, in the disk's local frame
+    pub(super) fn polar_tangents(
+        x: f64,
+        y: f64,
+        inner_radius: f64,
+        outer_radius: f64,
+    ) -> (Vec3, Vec3) {
+        let rho = (x * x + y * y).sqrt();
+        let radial = if rho > 0.0 {
+            Vec3::new(x / rho, y / rho, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        (
+            Vec3::new(-y, x, 0.0) * (2.0 * PI),
+            radial * (outer_radius - inner_radius),
+        )
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let denom = self.frame.w.dot(&r.direction());
+        if denom.abs() < 1e-12 {
+            return None; // ray is parallel to the disk
+        }
+        let t = self.frame.w.dot(&(self.center - r.origin())) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let p = r.at(t);
+        let local = self.frame.world_to_local(&(p - self.center));
+        let rho_squared = local.x * local.x + local.y * local.y;
+        if rho_squared > self.outer_radius * self.outer_radius
+            || rho_squared < self.inner_radius * self.inner_radius
+        {
+            return None;
+        }
+
+        let (u, v) = Disk::polar_uv(local.x, local.y, self.inner_radius, self.outer_radius);
+        let (dpdu, dpdv) =
+            Disk::polar_tangents(local.x, local.y, self.inner_radius, self.outer_radius);
+        Some(
+            HitRecord::new(p, r, self.frame.w, t, u, v, self.material.clone()).with_tangents(
+                self.frame.local_to_world(&dpdu),
+                self.frame.local_to_world(&dpdv),
+            ),
+        )
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
@@ -0,0 +1,295 @@
+use std::{path::Path, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, point3::Point3, ray::Ray, vec3::Vec3},
+    texture::perlin::Perlin,
+};
+
+use super::{aabb::AABB, triangle::intersect_triangle, HitRecord, Hittable};
+
+// A terrain surface y = height(x, z), sampled on a regular grid.
+// Each grid cell is split into two triangles, shaded with interpolated vertex normals.
+// Rays walk the grid cells they cross in order (a 2D DDA),
+// skipping cells whose height range they pass above or below.
+#[derive(Debug)]
+pub struct Heightfield {
+    nx: usize,
+    nz: usize,
+    heights: Vec<f64>, // world space heights, x-fastest
+    normals: Vec<Vec3>,
+    cell_heights: Vec<Interval>, // height range of every cell
+    corner: Point3,
+    cell_x: f64,
+    cell_z: f64,
+    material: Rc<dyn Material>,
+    bbox: AABB,
+}
+
+impl Heightfield {
+    // heights are given in [0, 1] on an nx * nz grid, x-fastest.
+    // The terrain spans from `corner` to `corner + size`, with size.y the height of a value of 1.
+    // corner is the terrain's corner with the smallest x and z, so size.x and size.z are positive.
+    pub fn new(
+        nx: usize,
+        nz: usize,
+        heights: Vec<f64>,
+        corner: Point3,
+        size: Vec3,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        assert!(nx >= 2 && nz >= 2, "Heightfield needs at least 2x2 samples");
+        assert_eq!(heights.len(), nx * nz, "Heightfield sample count mismatch");
+        // the grid is walked from `corner` towards increasing x and z
+        assert!(
+            size.x > 0.0 && size.z > 0.0,
+            "Heightfield needs a positive size along x and z"
+        );
+
+        let heights: Vec<f64> = heights.iter().map(|h| h * size.y).collect();
+        let cell_x = size.x / (nx - 1) as f64;
+        let cell_z = size.z / (nz - 1) as f64;
+
+        let at = |i: usize, j: usize| heights[j * nx + i];
+        let normals = (0..nz)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                // central differences, one-sided at the borders
+                let (i0, i1) = (i.saturating_sub(1), (i + 1).min(nx - 1));
+                let (j0, j1) = (j.saturating_sub(1), (j + 1).min(nz - 1));
+                let dhdx = (at(i1, j) - at(i0, j)) / ((i1 - i0) as f64 * cell_x);
+                let dhdz = (at(i, j1) - at(i, j0)) / ((j1 - j0) as f64 * cell_z);
+                Vec3::new(-dhdx, 1.0, -dhdz).unit()
+            })
+            .collect();
+
+        let cell_heights = (0..nz - 1)
+            .flat_map(|j| (0..nx - 1).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let corners = [at(i, j), at(i + 1, j), at(i, j + 1), at(i + 1, j + 1)];
+                Interval::new(
+                    corners.iter().cloned().fold(f64::INFINITY, f64::min),
+                    corners.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                )
+            })
+            .collect();
+
+        let (min_height, max_height) = heights
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &h| {
+                (lo.min(h), hi.max(h))
+            });
+        let bbox = AABB::new(
+            Interval::new(corner.x, corner.x + size.x).reorder(),
+            Interval::new(corner.y + min_height, corner.y + max_height),
+            Interval::new(corner.z, corner.z + size.z).reorder(),
+        );
+
+        Self {
+            nx,
+            nz,
+            heights,
+            normals,
+            cell_heights,
+            corner,
+            cell_x,
+            cell_z,
+            material,
+            bbox,
+        }
+    }
+
+    // Uses the luminance of an image as heights, with the top row of the image at corner.z.
+    pub fn from_image(
+        image_path: impl AsRef<Path>,
+        corner: Point3,
+        size: Vec3,
+        material: Rc<dyn Material>,
+    ) -> Result<Self, image::ImageError> {
+        let image = image::open(image_path)?.into_luma16();
+        let heights = image
+            .pixels()
+            .map(|pixel| pixel.0[0] as f64 / u16::MAX as f64)
+            .collect();
+        Ok(Self::new(
+            image.width() as usize,
+            image.height() as usize,
+            heights,
+            corner,
+            size,
+            material,
+        ))
+    }
+
+    // Generates rolling hills from Perlin turbulence.
+    // `scale` is the noise frequency over the whole terrain.
+    // The same seed gives the same terrain.
+    pub fn from_perlin(
+        resolution: usize,
+        scale: f64,
+        depth: i32,
+        seed: u64,
+        corner: Point3,
+        size: Vec3,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let noise = Perlin::with_seed(seed);
+        let inv_res = 1.0 / (resolution - 1) as f64;
+        let heights = (0..resolution)
+            .flat_map(|j| (0..resolution).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let p = Point3::new(i as f64 * inv_res, 0.5, j as f64 * inv_res) * scale;
+                noise.turbulence(&p, depth).min(1.0)
+            })
+            .collect();
+        Self::new(resolution, resolution, heights, corner, size, material)
+    }
+
+    fn vertex(&self, i: usize, j: usize) -> (Point3, Vec3) {
+        let idx = j * self.nx + i;
+        (
+            self.corner
+                + Vec3::new(
+                    i as f64 * self.cell_x,
+                    self.heights[idx],
+                    j as f64 * self.cell_z,
+                ),
+            self.normals[idx],
+        )
+    }
+
+    fn hit_cell(&self, r: &Ray, ray_t: Interval, i: usize, j: usize) -> Option<HitRecord> {
+        let v00 = self.vertex(i, j);
+        let v10 = self.vertex(i + 1, j);
+        let v01 = self.vertex(i, j + 1);
+        let v11 = self.vertex(i + 1, j + 1);
+
+        let (t, (a, b, c), b1, b2) = [(v00, v10, v11), (v00, v11, v01)]
+            .into_iter()
+            .filter_map(|tri| {
+                intersect_triangle(r, ray_t, tri.0 .0, tri.1 .0, tri.2 .0)
+                    .map(|(t, b1, b2)| (t, tri, b1, b2))
+            })
+            .min_by(|x, y| x.0.total_cmp(&y.0))?;
+
+        let p = r.at(t);
+        // the triangles wind clockwise seen from above
+        let face_normal = (c.0 - a.0).cross(&(b.0 - a.0)).unit();
+        let shading_normal = a.1 * (1.0 - b1 - b2) + b.1 * b1 + c.1 * b2;
+
+        let local = p - self.corner;
+        let size_x = self.cell_x * (self.nx - 1) as f64;
+        let size_z = self.cell_z * (self.nz - 1) as f64;
+        let u = local.x / size_x;
+        let v = 1.0 - local.z / size_z;
+        // slopes of the triangle's plane
+        let dhdx = -face_normal.x / face_normal.y;
+        let dhdz = -face_normal.z / face_normal.y;
+        let dpdu = Vec3::new(size_x, dhdx * size_x, 0.0);
+        let dpdv = Vec3::new(0.0, -dhdz * size_z, -size_z);
+
+        Some(
+            HitRecord::new(p, r, face_normal, t, u, v, self.material.clone())
+                .with_tangents(dpdu, dpdv)
+                .with_shading_normal(shading_normal),
+        )
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let inside = self.bbox.hit_interval(r, ray_t)?;
+        let o = r.origin() - self.corner;
+        let d = r.direction();
+
+        // the cell containing the point where the ray enters the box
+        let start = o + d * inside.start;
+        let cell_of =
+            |x: f64, size: f64, n: usize| ((x / size).floor().max(0.0) as usize).min(n - 2);
+        let mut i = cell_of(start.x, self.cell_x, self.nx);
+        let mut j = cell_of(start.z, self.cell_z, self.nz);
+
+        // t at which the ray crosses the next cell boundary along x and z, and the t between them
+        let axis_setup = |o: f64, d: f64, cell: usize, size: f64| {
+            if d > 0.0 {
+                (((cell + 1) as f64 * size - o) / d, size / d)
+            } else if d < 0.0 {
+                ((cell as f64 * size - o) / d, -size / d)
+            } else {
+                (f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (mut t_next_x, t_delta_x) = axis_setup(o.x, d.x, i, self.cell_x);
+        let (mut t_next_z, t_delta_z) = axis_setup(o.z, d.z, j, self.cell_z);
+
+        let mut t_enter = inside.start;
+        loop {
+            let t_exit = t_next_x.min(t_next_z).min(inside.end);
+
+            // only test the triangles if the ray passes through the cell's height range
+            let y0 = o.y + d.y * t_enter;
+            let y1 = o.y + d.y * t_exit;
+            let cell = self.cell_heights[j * (self.nx - 1) + i];
+            if y0.min(y1) <= cell.end && y0.max(y1) >= cell.start {
+                if let Some(rec) = self.hit_cell(r, ray_t, i, j) {
+                    return Some(rec);
+                }
+            }
+
+            if t_exit >= inside.end {
+                return None;
+            }
+            if t_next_x < t_next_z {
+                if (d.x > 0.0 && i + 2 >= self.nx) || (d.x < 0.0 && i == 0) {
+                    return None;
+                }
+                i = if d.x > 0.0 { i + 1 } else { i - 1 };
+                t_next_x += t_delta_x;
+            } else {
+                if (d.z > 0.0 && j + 2 >= self.nz) || (d.z < 0.0 && j == 0) {
+                    return None;
+                }
+                j = if d.z > 0.0 { j + 1 } else { j - 1 };
+                t_next_z += t_delta_z;
+            }
+            t_enter = t_exit;
+        }
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, primitive::color::Color};
+
+    fn flat(size: Vec3) -> Heightfield {
+        Heightfield::new(
+            2,
+            2,
+            vec![0.5; 4],
+            Point3::zero(),
+            size,
+            Rc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+        )
+    }
+
+    #[test]
+    fn rays_from_above_hit() {
+        let terrain = flat(Vec3::new(4.0, 2.0, 4.0));
+        let r = Ray::new(Point3::new(1.0, 5.0, 3.0), Vec3::new(0.0, -1.0, 0.0), 0.0);
+        let rec = terrain
+            .hit(&r, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((rec.p.y - 1.0).abs() < 1e-9, "{:?}", rec.p);
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_sizes_are_rejected() {
+        flat(Vec3::new(-4.0, 2.0, 4.0));
+    }
+}
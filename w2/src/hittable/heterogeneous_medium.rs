@@ -0,0 +1,165 @@
+use std::rc::Rc;
+
+use crate::{
+    material::{isotropic::Isotropic, Material},
+    primitive::{color::Color, interval::Interval, point3::Point3, ray::Ray},
+};
+
+use super::{aabb::AABB, density_grid::DensityGrid, HitRecord, Hittable};
+
+// A volume whose density varies in space, given by a DensityGrid stretched over `bounds`.
+// Collisions are sampled with delta tracking:
+// tentative collisions are drawn against a constant majorant density,
+// and accepted with probability density / majorant.
+// Shadow rays use ratio tracking instead, which weighs every tentative collision
+// rather than stopping at the first one, so that they see partial transmittance.
+#[derive(Debug)]
+pub struct HeterogeneousMedium {
+    grid: Rc<DensityGrid>,
+    bounds: AABB,
+    density_scale: f64,
+    majorant: f64,
+    phase_function: Rc<dyn Material>,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(grid: Rc<DensityGrid>, bounds: AABB, density_scale: f64, albedo: Color) -> Self {
+        let majorant = grid.max_density() * density_scale;
+        Self {
+            grid,
+            bounds,
+            density_scale,
+            majorant,
+            phase_function: Rc::new(Isotropic::new(albedo)),
+        }
+    }
+
+    // Overrides the majorant, which defaults to the maximum density of the grid.
+    // A larger majorant only wastes tentative collisions, but one below the actual density
+    // would bias the result, so the majorant never goes below the grid's maximum.
+    pub fn with_majorant(mut self, majorant: f64) -> Self {
+        self.majorant = majorant.max(self.grid.max_density() * self.density_scale);
+        self
+    }
+
+    pub fn density(&self, p: &Point3) -> f64 {
+        let local = Point3::new(
+            Self::to_unit(p.x, self.bounds.axis_interval(0)),
+            Self::to_unit(p.y, self.bounds.axis_interval(1)),
+            Self::to_unit(p.z, self.bounds.axis_interval(2)),
+        );
+        self.grid.density(&local) * self.density_scale
+    }
+
+    fn to_unit(x: f64, axis: Interval) -> f64 {
+        (x - axis.start) / axis.size()
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let inside = self.bounds.hit_interval(r, ray_t)?;
+        if self.majorant <= 0.0 {
+            return None;
+        }
+
+        // distances are sampled in units of t, so account for the ray direction length
+        let inv_majorant = 1.0 / (self.majorant * r.direction().length());
+        let mut t = inside.start;
+        loop {
+            t -= (1.0 - rand::random::<f64>()).ln() * inv_majorant;
+            if t >= inside.end {
+                return None;
+            }
+            let p = r.at(t);
+            if rand::random::<f64>() * self.majorant < self.density(&p) {
+                return Some(HitRecord::in_medium(p, t, self.phase_function.clone()));
+            }
+        }
+    }
+
+    // Ratio tracking estimate of the fraction of light passing through the medium.
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        let Some(inside) = self.bounds.hit_interval(r, ray_t) else {
+            return 1.0;
+        };
+        if self.majorant <= 0.0 {
+            return 1.0;
+        }
+
+        let inv_majorant = 1.0 / (self.majorant * r.direction().length());
+        let mut transmittance = 1.0;
+        let mut t = inside.start;
+        loop {
+            t -= (1.0 - rand::random::<f64>()).ln() * inv_majorant;
+            if t >= inside.end {
+                return transmittance;
+            }
+            transmittance *= 1.0 - self.density(&r.at(t)) / self.majorant;
+        }
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::{constant_medium::ConstantMedium, sphere::Sphere},
+        material::lambertian::Lambertian,
+        primitive::vec3::Vec3,
+    };
+
+    #[test]
+    fn ratio_tracking_matches_beer_lambert() {
+        // a uniform grid filling a 2 unit cube, with a majorant well above the density
+        let grid = Rc::new(DensityGrid::new(2, 2, 2, vec![1.0; 8]).unwrap());
+        let bounds =
+            AABB::new_from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let medium = HeterogeneousMedium::new(grid, bounds, 0.7, Color::new(1.0, 1.0, 1.0))
+            .with_majorant(2.0);
+
+        // twice the unit direction, so that t and distance differ
+        let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0), 0.0);
+        let ray_t = Interval::new(0.0, f64::INFINITY);
+        let n = 200_000;
+        let mean = (0..n).map(|_| medium.transmittance(&r, ray_t)).sum::<f64>() / n as f64;
+        let expected = (-0.7 * 2.0_f64).exp();
+        assert!((mean - expected).abs() < 0.01, "{mean} vs {expected}");
+
+        // stopping halfway through halves the optical depth
+        let half = Interval::new(0.0, 2.5);
+        let mean = (0..n).map(|_| medium.transmittance(&r, half)).sum::<f64>() / n as f64;
+        assert!((mean - (-0.7_f64).exp()).abs() < 0.01, "{mean}");
+    }
+
+    #[test]
+    fn majorant_is_never_below_the_densest_voxel() {
+        let grid = Rc::new(DensityGrid::new(2, 1, 1, vec![0.5, 2.0]).unwrap());
+        let bounds = AABB::new_from_points(Point3::zero(), Point3::new(1.0, 1.0, 1.0));
+        let medium = || HeterogeneousMedium::new(grid.clone(), bounds, 3.0, Color::new(1, 1, 1));
+        assert_eq!(medium().majorant, 6.0);
+        assert_eq!(medium().with_majorant(10.0).majorant, 10.0);
+        for majorant in [4.0, 0.0, -1.0, f64::NAN] {
+            assert_eq!(medium().with_majorant(majorant).majorant, 6.0, "{majorant}");
+        }
+    }
+
+    #[test]
+    fn constant_medium_transmittance_is_exact() {
+        let boundary = Rc::new(Sphere::new(
+            Point3::zero(),
+            1.0,
+            Rc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+        ));
+        let medium = ConstantMedium::new(boundary, 0.5, Color::new(1.0, 1.0, 1.0));
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 2.0), 0.0);
+        let t = medium.transmittance(&r, Interval::new(0.0, f64::INFINITY));
+        assert!((t - (-0.5_f64 * 2.0).exp()).abs() < 1e-9, "{t}");
+        // the ray stops before reaching the sphere
+        assert_eq!(medium.transmittance(&r, Interval::new(0.0, 1.0)), 1.0);
+    }
+}
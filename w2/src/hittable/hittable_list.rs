@@ -3,13 +3,17 @@ use std::rc::Rc;
 use crate::primitive::{interval::Interval, ray::Ray};
 
 use super::{
-    aabb::{AABB, EMPTY_AABB},
+    aabb::{AABB, EMPTY_AABB, UNIVERSE_AABB},
+    bvh::BVHNode,
     HitRecord, Hittable,
 };
 
 #[derive(Debug)]
 pub struct HittableList {
     pub objects: Vec<Rc<dyn Hittable>>,
+    // objects with infinite bounding boxes, such as planes.
+    // These are kept apart so that they never end up inside a BVH.
+    pub unbounded: Vec<Rc<dyn Hittable>>,
     bbox: AABB,
 }
 
@@ -23,11 +27,16 @@ impl HittableList {
     pub fn new() -> Self {
         Self {
             objects: Vec::new(),
+            unbounded: Vec::new(),
             bbox: EMPTY_AABB,
         }
     }
 
     pub fn add(&mut self, object: Rc<dyn Hittable>) {
+        if object.bounding_box().is_infinite() {
+            self.unbounded.push(object);
+            return;
+        }
         if self.bbox == EMPTY_AABB {
             self.bbox = object.bounding_box();
         } else {
@@ -35,14 +44,27 @@ impl HittableList {
         }
         self.objects.push(object);
     }
+
+    // Puts the bounded objects into a BVH, leaving unbounded objects next to it.
+    pub fn into_bvh(self) -> Self {
+        let mut world = HittableList::new();
+        if !self.objects.is_empty() {
+            world.add(Rc::new(BVHNode::from(self.objects.as_slice())));
+        }
+        self.unbounded
+            .into_iter()
+            .for_each(|object| world.add(object));
+        world
+    }
 }
 
 impl Hittable for HittableList {
     fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
         self.objects
             .iter()
+            .chain(self.unbounded.iter())
             .fold((None, ray_t.end), |(ret, mut closest_so_far), object| {
-                if let Some(rec) = object.hit(
+                if let Some(rec) = object.hit_opaque(
                     r,
                     Interval {
                         start: ray_t.start,
@@ -61,7 +83,22 @@ impl Hittable for HittableList {
             .0
     }
 
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        let mut transmittance = 1.0;
+        for object in self.objects.iter().chain(self.unbounded.iter()) {
+            transmittance *= object.transmittance(r, ray_t);
+            if transmittance <= 0.0 {
+                return 0.0;
+            }
+        }
+        transmittance
+    }
+
     fn bounding_box(&self) -> AABB {
-        self.bbox
+        if self.unbounded.is_empty() {
+            self.bbox
+        } else {
+            UNIVERSE_AABB
+        }
     }
 }
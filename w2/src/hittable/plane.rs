@@ -0,0 +1,76 @@
+use std::rc::Rc;
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{
+    aabb::{AABB, UNIVERSE_AABB},
+    HitRecord, Hittable,
+};
+
+// An infinite plane through `point`, facing `normal`.
+// Its bounding box is infinite, so HittableList keeps it out of the BVH.
+#[derive(Debug)]
+pub struct Plane {
+    point: Point3,
+    frame: ONB,
+    inv_uv_scale: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, material: Rc<dyn Material>) -> Self {
+        Self::new_with_uv_scale(point, normal, 1.0, material)
+    }
+
+    // uv_scale is the world space size of one texture tile.
+    pub fn new_with_uv_scale(
+        point: Point3,
+        normal: Vec3,
+        uv_scale: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        Self {
+            point,
+            frame: ONB::new(&normal),
+            inv_uv_scale: 1.0 / uv_scale,
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let denom = self.frame.w.dot(&r.direction());
+        if denom.abs() < 1e-12 {
+            return None; // ray is parallel to the plane
+        }
+        let t = self.frame.w.dot(&(self.point - r.origin())) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        // Project the hit point back onto the plane.
+        // This removes the rounding error of r.at(t), which is large far away from the origin,
+        // and keeps axis-aligned planes exactly on their axis for spatial textures.
+        let p = r.at(t);
+        let offset = p - self.point;
+        let p = p - self.frame.w * offset.dot(&self.frame.w);
+
+        // world space coordinates along the plane, wrapped so that every texture tiles
+        let u = (offset.dot(&self.frame.u) * self.inv_uv_scale).rem_euclid(1.0);
+        let v = (offset.dot(&self.frame.v) * self.inv_uv_scale).rem_euclid(1.0);
+
+        let uv_scale = 1.0 / self.inv_uv_scale;
+        Some(
+            HitRecord::new(p, r, self.frame.w, t, u, v, self.material.clone())
+                .with_tangents(self.frame.u * uv_scale, self.frame.v * uv_scale),
+        )
+    }
+
+    fn bounding_box(&self) -> AABB {
+        UNIVERSE_AABB
+    }
+}
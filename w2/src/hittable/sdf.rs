@@ -0,0 +1,229 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, sphere::Sphere, HitRecord, Hittable};
+
+// A signed distance function: negative inside the shape, positive outside.
+// The returned value must never overestimate the distance to the surface,
+// otherwise sphere tracing can step through it.
+pub trait Sdf: std::fmt::Debug {
+    fn distance(&self, p: &Point3) -> f64;
+}
+
+// Wraps a closure as an Sdf.
+pub struct SdfFn<F: Fn(&Point3) -> f64>(pub F);
+
+impl<F: Fn(&Point3) -> f64> std::fmt::Debug for SdfFn<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SdfFn")
+    }
+}
+
+impl<F: Fn(&Point3) -> f64> Sdf for SdfFn<F> {
+    fn distance(&self, p: &Point3) -> f64 {
+        (self.0)(p)
+    }
+}
+
+#[derive(Debug)]
+pub struct SdfSphere {
+    pub center: Point3,
+    pub radius: f64,
+}
+
+impl Sdf for SdfSphere {
+    fn distance(&self, p: &Point3) -> f64 {
+        (*p - self.center).length() - self.radius
+    }
+}
+
+#[derive(Debug)]
+pub struct SdfBox {
+    pub center: Point3,
+    pub half_extents: Vec3,
+}
+
+impl Sdf for SdfBox {
+    fn distance(&self, p: &Point3) -> f64 {
+        let q = *p - self.center;
+        let q = Vec3::new(
+            q.x.abs() - self.half_extents.x,
+            q.y.abs() - self.half_extents.y,
+            q.z.abs() - self.half_extents.z,
+        );
+        let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).length();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside
+    }
+}
+
+// A box whose edges are rounded off with the given radius.
+// The rounding stays within half_extents.
+#[derive(Debug)]
+pub struct SdfRoundedBox {
+    pub center: Point3,
+    pub half_extents: Vec3,
+    pub radius: f64,
+}
+
+impl Sdf for SdfRoundedBox {
+    fn distance(&self, p: &Point3) -> f64 {
+        let inner = SdfBox {
+            center: self.center,
+            half_extents: self.half_extents - Vec3::new(self.radius, self.radius, self.radius),
+        };
+        inner.distance(p) - self.radius
+    }
+}
+
+// A line segment from a to b, thickened by radius.
+#[derive(Debug)]
+pub struct SdfCapsule {
+    pub a: Point3,
+    pub b: Point3,
+    pub radius: f64,
+}
+
+impl Sdf for SdfCapsule {
+    fn distance(&self, p: &Point3) -> f64 {
+        let pa = *p - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(&ba) / ba.length_squared()).clamp(0.0, 1.0);
+        (pa - ba * h).length() - self.radius
+    }
+}
+
+// Union of two shapes, with the seam smoothed over a distance of roughly k.
+#[derive(Debug)]
+pub struct SmoothUnion {
+    pub a: Rc<dyn Sdf>,
+    pub b: Rc<dyn Sdf>,
+    pub k: f64,
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f64 {
+        let d1 = self.a.distance(p);
+        let d2 = self.b.distance(p);
+        if self.k <= 0.0 {
+            return d1.min(d2);
+        }
+        // polynomial smooth minimum
+        let h = (0.5 + 0.5 * (d2 - d1) / self.k).clamp(0.0, 1.0);
+        d2 + (d1 - d2) * h - self.k * h * (1.0 - h)
+    }
+}
+
+// Morphs between two shapes, giving a when t = 0 and b when t = 1.
+#[derive(Debug)]
+pub struct Blend {
+    pub a: Rc<dyn Sdf>,
+    pub b: Rc<dyn Sdf>,
+    pub t: f64,
+}
+
+impl Sdf for Blend {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.a.distance(p) * (1.0 - self.t) + self.b.distance(p) * self.t
+    }
+}
+
+// Renders an Sdf with sphere tracing.
+// Marching only happens inside the user supplied bounding box,
+// which must enclose the whole surface.
+#[derive(Debug)]
+pub struct SdfObject {
+    sdf: Rc<dyn Sdf>,
+    material: Rc<dyn Material>,
+    bbox: AABB,
+    max_steps: u32,
+    epsilon: f64,
+}
+
+impl SdfObject {
+    pub fn new(sdf: Rc<dyn Sdf>, bbox: AABB, material: Rc<dyn Material>) -> Self {
+        Self {
+            sdf,
+            material,
+            bbox,
+            max_steps: 256,
+            epsilon: 1e-4,
+        }
+    }
+
+    // Overrides the maximum number of marching steps per ray,
+    // and the distance to the surface at which a ray counts as hitting it.
+    pub fn with_precision(mut self, max_steps: u32, epsilon: f64) -> Self {
+        self.max_steps = max_steps;
+        self.epsilon = epsilon;
+        self
+    }
+
+    // central differences of the distance field
+    fn gradient(&self, p: &Point3) -> Vec3 {
+        let h = self.epsilon;
+        let dx = Vec3::new(h, 0.0, 0.0);
+        let dy = Vec3::new(0.0, h, 0.0);
+        let dz = Vec3::new(0.0, 0.0, h);
+        Vec3::new(
+            self.sdf.distance(&(*p + dx)) - self.sdf.distance(&(*p - dx)),
+            self.sdf.distance(&(*p + dy)) - self.sdf.distance(&(*p - dy)),
+            self.sdf.distance(&(*p + dz)) - self.sdf.distance(&(*p - dz)),
+        )
+    }
+}
+
+impl Hittable for SdfObject {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let inside = self.bbox.hit_interval(r, ray_t)?;
+        let inv_len = 1.0 / r.direction().length();
+
+        let mut t = inside.start;
+        // A ray that starts on the surface, like a scattered one, first has to get away from it.
+        let mut leaving = self.sdf.distance(&r.at(t)).abs() < self.epsilon;
+
+        for _ in 0..self.max_steps {
+            if t > inside.end {
+                return None;
+            }
+            let p = r.at(t);
+            // the absolute value lets rays march from inside to the outside too
+            let d = self.sdf.distance(&p).abs();
+            if leaving {
+                leaving = d < self.epsilon;
+            } else if d < self.epsilon {
+                if !ray_t.surrounds(t) {
+                    return None;
+                }
+                let outward_normal = self.gradient(&p).unit();
+                // spherical mapping of the normal, as a surface parameterization is unknown
+                let theta = (-outward_normal.y).acos();
+                let phi = (-outward_normal.z).atan2(outward_normal.x) + PI;
+                // the tangents follow that mapping, as on a unit sphere
+                let (dpdu, dpdv) = Sphere::tangents(&outward_normal, 1.0);
+                return Some(
+                    HitRecord::new(
+                        p,
+                        r,
+                        outward_normal,
+                        t,
+                        phi / (2.0 * PI),
+                        theta / PI,
+                        self.material.clone(),
+                    )
+                    .with_tangents(dpdu, dpdv),
+                );
+            }
+            t += d.max(self.epsilon) * inv_len;
+        }
+        None
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
@@ -67,6 +67,20 @@ impl Sphere {
             theta / std::f64::consts::PI,       // v
         )
     }
+
+    // dp/du and dp/dv of get_uv's mapping, at the point with unit normal n
+    pub(super) fn tangents(n: &Vec3, radius: f64) -> (Vec3, Vec3) {
+        let pi = std::f64::consts::PI;
+        let dpdu = Vec3::new(n.z, 0.0, -n.x) * (2.0 * pi * radius);
+        let rho = (n.x * n.x + n.z * n.z).sqrt();
+        let dpdv = if rho > 1e-12 {
+            Vec3::new(-n.y * n.x / rho, rho, -n.y * n.z / rho) * (pi * radius)
+        } else {
+            // at the poles, v runs along any meridian
+            Vec3::new(-n.y, 0.0, 0.0) * (pi * radius)
+        };
+        (dpdu, dpdv)
+    }
 }
 
 impl Hittable for Sphere {
@@ -98,15 +112,11 @@ impl Hittable for Sphere {
         let outward_normal = (p - center) / self.radius;
 
         let (u, v) = Sphere::get_uv(&outward_normal);
-        Some(HitRecord::new(
-            p,
-            r,
-            outward_normal,
-            t,
-            u,
-            v,
-            self.material.clone(),
-        ))
+        let (dpdu, dpdv) = Sphere::tangents(&outward_normal, self.radius);
+        Some(
+            HitRecord::new(p, r, outward_normal, t, u, v, self.material.clone())
+                .with_tangents(dpdu, dpdv),
+        )
     }
     fn bounding_box(&self) -> AABB {
         self.bbox
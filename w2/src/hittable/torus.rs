@@ -0,0 +1,317 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+// A torus lying in the plane perpendicular to `axis`.
+// major_radius is the distance from the center to the middle of the tube,
+// minor_radius is the radius of the tube itself.
+#[derive(Debug)]
+pub struct Torus {
+    center: Point3,
+    major_radius: f64,
+    minor_radius: f64,
+    frame: ONB,
+    material: Rc<dyn Material>,
+    bbox: AABB,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Self {
+        let major_radius = major_radius.max(0.0);
+        let minor_radius = minor_radius.max(0.0);
+        // the circle through the middle of the tube, swept by a ball of the tube's radius
+        let ring = AABB::new_from_disk(center, &axis, major_radius);
+        let bbox = AABB::new(
+            ring.axis_interval(0).expand(minor_radius),
+            ring.axis_interval(1).expand(minor_radius),
+            ring.axis_interval(2).expand(minor_radius),
+        );
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+            frame: ONB::new(&axis),
+            material,
+            bbox,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // Work in the local frame with a unit direction, so the quartic is well conditioned.
+        // s is the distance along the unit direction, s = t * |direction|.
+        let dir_len = r.direction().length();
+        let o = self.frame.world_to_local(&(r.origin() - self.center));
+        let d = self.frame.world_to_local(&r.direction()) / dir_len;
+
+        // Only look for roots inside the bounding sphere of the torus,
+        // which gives a finite interval for root isolation.
+        let bound = self.major_radius + self.minor_radius;
+        let od = o.dot(&d);
+        let discriminant = od * od - (o.length_squared() - bound * bound);
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+        let s_range = Interval::new(
+            (-od - sqrtd).max(ray_t.start * dir_len),
+            (-od + sqrtd).min(ray_t.end * dir_len),
+        );
+        if s_range.start >= s_range.end {
+            return None;
+        }
+
+        // (|p|^2 + R^2 - r^2)^2 = 4R^2 (p.x^2 + p.y^2), expanded along p = o + s * d
+        let r2 = self.major_radius * self.major_radius;
+        let k = o.length_squared() + r2 - self.minor_radius * self.minor_radius;
+        let coeffs = [
+            k * k - 4.0 * r2 * (o.x * o.x + o.y * o.y),
+            4.0 * od * k - 8.0 * r2 * (o.x * d.x + o.y * d.y),
+            4.0 * od * od + 2.0 * k - 4.0 * r2 * (d.x * d.x + d.y * d.y),
+            4.0 * od,
+            1.0,
+        ];
+
+        let s = polynomial_roots(&coeffs, s_range)
+            .into_iter()
+            .find(|&s| ray_t.surrounds(s / dir_len))?;
+        let t = s / dir_len;
+
+        let local = o + d * s;
+        let rho = (local.x * local.x + local.y * local.y).sqrt();
+        // the normal points away from the nearest point on the tube's center circle
+        let ring_point = if rho > 0.0 {
+            Vec3::new(local.x, local.y, 0.0) * (self.major_radius / rho)
+        } else {
+            Vec3::zero()
+        };
+        let outward_normal = self.frame.local_to_world(&(local - ring_point)).unit();
+
+        let u = (local.y.atan2(local.x) + PI) / (2.0 * PI);
+        let v = (local.z.atan2(rho - self.major_radius) + PI) / (2.0 * PI);
+        // around the main axis, and around the tube
+        let dpdu = Vec3::new(-local.y, local.x, 0.0) * (2.0 * PI);
+        let radial = if rho > 0.0 {
+            Vec3::new(local.x / rho, local.y / rho, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let dpdv = (radial * -local.z + Vec3::new(0.0, 0.0, rho - self.major_radius)) * (2.0 * PI);
+        Some(
+            HitRecord::new(r.at(t), r, outward_normal, t, u, v, self.material.clone())
+                .with_tangents(
+                    self.frame.local_to_world(&dpdu),
+                    self.frame.local_to_world(&dpdv),
+                ),
+        )
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
+
+// Finds the real roots of the polynomial sum(coeffs[i] * x^i) within range, in ascending order.
+// The roots of the derivative split the range into monotonic pieces,
+// each containing at most one root, which is then found by bisection.
+fn polynomial_roots(coeffs: &[f64], range: Interval) -> Vec<f64> {
+    let degree = coeffs.len() - 1;
+    if degree == 1 {
+        let root = -coeffs[0] / coeffs[1];
+        return if range.contains(root) {
+            vec![root]
+        } else {
+            vec![]
+        };
+    }
+
+    let derivative: Vec<f64> = coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * i as f64)
+        .collect();
+
+    let mut bounds = vec![range.start];
+    bounds.extend(polynomial_roots(&derivative, range));
+    bounds.push(range.end);
+
+    let eval = |x: f64| coeffs.iter().rev().fold(0.0, |acc, c| acc * x + c);
+    // Values within the rounding error of the evaluation count as zero,
+    // so that a double root at a root of the derivative (a tangent ray) is found once,
+    // instead of being missed or split in two by the noise around it.
+    let eval_rounded = |x: f64| {
+        let magnitude = coeffs
+            .iter()
+            .rev()
+            .fold(0.0, |acc, c| acc * x.abs() + c.abs());
+        let value = eval(x);
+        if value.abs() <= 8.0 * degree as f64 * f64::EPSILON * magnitude {
+            0.0
+        } else {
+            value
+        }
+    };
+    let values: Vec<f64> = bounds.iter().map(|&x| eval_rounded(x)).collect();
+
+    let mut roots = Vec::new();
+    for (pair, f) in bounds.windows(2).zip(values.windows(2)) {
+        let (mut lo, mut hi) = (pair[0], pair[1]);
+        let (f_lo, f_hi) = (f[0], f[1]);
+        if f_lo == 0.0 {
+            roots.push(lo);
+            continue;
+        }
+        if f_hi == 0.0 || f_lo.signum() == f_hi.signum() {
+            continue;
+        }
+        for _ in 0..64 {
+            let mid = 0.5 * (lo + hi);
+            if eval(mid).signum() == f_lo.signum() {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        roots.push(0.5 * (lo + hi));
+    }
+    if values.last() == Some(&0.0) {
+        roots.push(range.end);
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, primitive::color::Color};
+
+    fn assert_roots(roots: &[f64], expected: &[f64]) {
+        assert_eq!(roots.len(), expected.len(), "{roots:?} vs {expected:?}");
+        for (root, want) in roots.iter().zip(expected) {
+            assert!((root - want).abs() < 1e-9, "{roots:?} vs {expected:?}");
+        }
+    }
+
+    fn torus() -> Torus {
+        Torus::new(
+            Point3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            0.5,
+            Rc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+        )
+    }
+
+    #[test]
+    fn known_quartic_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        let coeffs = [24.0, -50.0, 35.0, -10.0, 1.0];
+        assert_roots(
+            &polynomial_roots(&coeffs, Interval::new(-10.0, 10.0)),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        assert_roots(
+            &polynomial_roots(&coeffs, Interval::new(1.5, 3.5)),
+            &[2.0, 3.0],
+        );
+        // x^4 + 1 has no real roots
+        assert_roots(
+            &polynomial_roots(&[1.0, 0.0, 0.0, 0.0, 1.0], Interval::new(-10.0, 10.0)),
+            &[],
+        );
+    }
+
+    #[test]
+    fn double_roots() {
+        // (x - 1)^2 (x + 2)(x - 3) = x^4 - 3x^3 - 3x^2 + 11x - 6
+        let coeffs = [-6.0, 11.0, -3.0, -3.0, 1.0];
+        assert_roots(
+            &polynomial_roots(&coeffs, Interval::new(-10.0, 10.0)),
+            &[-2.0, 1.0, 3.0],
+        );
+        // (x - 1)^2 (x - 2)^2 = x^4 - 6x^3 + 13x^2 - 12x + 4
+        let coeffs = [4.0, -12.0, 13.0, -6.0, 1.0];
+        assert_roots(
+            &polynomial_roots(&coeffs, Interval::new(-10.0, 10.0)),
+            &[1.0, 2.0],
+        );
+    }
+
+    #[test]
+    fn ray_through_the_hole_along_the_axis_misses() {
+        let r = Ray::new(Point3::new(0.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        assert!(torus()
+            .hit(&r, Interval::new(0.001, f64::INFINITY))
+            .is_none());
+    }
+
+    #[test]
+    fn ray_grazing_the_inner_hole() {
+        let torus = torus();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        // in the plane of the torus, passing just inside the inner edge of the tube at 1.5,
+        // so it crosses the tube, the hole, and the tube again
+        let offset: f64 = 1.49;
+        let outer = (2.5 * 2.5 - offset * offset).sqrt();
+        let inner = (1.5 * 1.5 - offset * offset).sqrt();
+        let r = Ray::new(
+            Point3::new(-5.0, 0.0, offset),
+            Vec3::new(2.0, 0.0, 0.0),
+            0.0,
+        );
+
+        let mut crossings = Vec::new();
+        let mut start = ray_t.start;
+        while let Some(rec) = torus.hit(&r, Interval::new(start, ray_t.end)) {
+            crossings.push(r.at(rec.t).x);
+            start = rec.t + 1e-6;
+        }
+        assert_roots(&crossings, &[-outer, -inner, inner, outer]);
+
+        // just outside the inner edge, the ray stays in the tube across the hole
+        let r = Ray::new(Point3::new(-5.0, 0.0, 1.51), Vec3::new(2.0, 0.0, 0.0), 0.0);
+        let rec = torus.hit(&r, ray_t).unwrap();
+        let exit = torus
+            .hit(&r, Interval::new(rec.t + 1e-6, ray_t.end))
+            .unwrap();
+        assert!(r.at(exit.t).x > 1.9, "{}", r.at(exit.t).x);
+        assert!(torus
+            .hit(&r, Interval::new(exit.t + 1e-6, ray_t.end))
+            .is_none());
+    }
+
+    #[test]
+    fn tangent_ray_touches_the_top_of_the_tube() {
+        // the plane y = 0.5 touches the tube along a circle, so the ray meets it at two double roots
+        let r = Ray::new(Point3::new(-5.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let torus = torus();
+        let rec = torus
+            .hit(&r, Interval::new(0.001, f64::INFINITY))
+            .expect("the tangent ray should touch the tube");
+        assert!((rec.p.x + 2.0).abs() < 1e-3, "{:?}", rec.p);
+        assert!(rec.normal.y > 0.99, "{:?}", rec.normal);
+
+        // and touches it again on the far side of the hole
+        let rec = torus
+            .hit(&r, Interval::new(rec.t + 0.1, f64::INFINITY))
+            .expect("the tangent ray should touch the far side of the tube");
+        assert!((rec.p.x - 2.0).abs() < 1e-3, "{:?}", rec.p);
+        assert!(rec.normal.y > 0.99, "{:?}", rec.normal);
+        assert!(torus
+            .hit(&r, Interval::new(rec.t + 0.1, f64::INFINITY))
+            .is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use crate::{
+    material::Material,
+    primitive::{interval::Interval, onb::ONB, point3::Point3, ray::Ray, vec3::Vec3},
+};
+
+use super::{aabb::AABB, HitRecord, Hittable};
+
+// A single triangle, usually one face of a Mesh.
+// The geometric normal follows the winding order a, b, c.
+// With vertex normals, the shading normal is interpolated across the face.
+#[derive(Debug)]
+pub struct Triangle {
+    vertices: [Point3; 3],
+    normals: Option<[Vec3; 3]>,
+    uvs: [(f64, f64); 3],
+    material: Rc<dyn Material>,
+    bbox: AABB,
+}
+
+impl Triangle {
+    pub fn new(a: Point3, b: Point3, c: Point3, material: Rc<dyn Material>) -> Self {
+        let bbox =
+            AABB::surrounding_box(&AABB::new_from_points(a, b), &AABB::new_from_points(c, c));
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            // barycentric coordinates of b and c, when no UVs are given
+            uvs: [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            material,
+            bbox,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [(f64, f64); 3]) -> Self {
+        self.uvs = uvs;
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let [a, b, c] = self.vertices;
+        let (t, b1, b2) = intersect_triangle(r, ray_t, a, b, c)?;
+        let b0 = 1.0 - b1 - b2;
+
+        let outward_normal = (b - a).cross(&(c - a)).unit();
+        let [uv0, uv1, uv2] = self.uvs;
+        let u = uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2;
+        let v = uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2;
+        let (dpdu, dpdv) = triangle_tangents(self.vertices, self.uvs, &outward_normal);
+
+        let rec = HitRecord::new(r.at(t), r, outward_normal, t, u, v, self.material.clone())
+            .with_tangents(dpdu, dpdv);
+        Some(match self.normals {
+            Some([na, nb, nc]) => rec.with_shading_normal(na * b0 + nb * b1 + nc * b2),
+            None => rec,
+        })
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
+
+// dp/du and dp/dv of a triangle, from the change of position and uv along its edges.
+// Triangles with degenerate uvs get an arbitrary frame around their normal.
+pub(super) fn triangle_tangents(
+    [a, b, c]: [Point3; 3],
+    [uv0, uv1, uv2]: [(f64, f64); 3],
+    normal: &Vec3,
+) -> (Vec3, Vec3) {
+    let (du02, dv02) = (uv0.0 - uv2.0, uv0.1 - uv2.1);
+    let (du12, dv12) = (uv1.0 - uv2.0, uv1.1 - uv2.1);
+    let (dp02, dp12) = (a - c, b - c);
+    let det = du02 * dv12 - dv02 * du12;
+    if det.abs() < 1e-12 {
+        let frame = ONB::new(normal);
+        return (frame.u, frame.v);
+    }
+    let inv_det = 1.0 / det;
+    (
+        (dp02 * dv12 - dp12 * dv02) * inv_det,
+        (dp12 * du02 - dp02 * du12) * inv_det,
+    )
+}
+
+// Möller–Trumbore ray-triangle intersection.
+// Returns t and the barycentric weights of b and c.
+pub(super) fn intersect_triangle(
+    r: &Ray,
+    ray_t: Interval,
+    a: Point3,
+    b: Point3,
+    c: Point3,
+) -> Option<(f64, f64, f64)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let pvec = r.direction().cross(&edge2);
+    let det = edge1.dot(&pvec);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let tvec = r.origin() - a;
+    let b1 = tvec.dot(&pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+    let qvec = tvec.cross(&edge1);
+    let b2 = r.direction().dot(&qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(&qvec) * inv_det;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+    Some((t, b1, b2))
+}
@@ -0,0 +1,247 @@
+use std::rc::Rc;
+
+use crate::primitive::{interval::Interval, ray::Ray};
+
+use super::{
+    aabb::{AABB, EMPTY_AABB, ROUNDING_PAD},
+    hittable_list::HittableList,
+    HitRecord, Hittable,
+};
+
+// Number of children per node.
+// Kept as a power of 2 so that the lane loops below map cleanly onto SIMD registers.
+pub const BVH_WIDTH: usize = 4;
+
+// Bounding boxes of all children of a wide node, stored as structure-of-arrays.
+// Each axis is tested for every lane at once,
+// which lets the compiler vectorize the slab test without any explicit intrinsics.
+#[derive(Debug, Clone, Copy)]
+pub struct AABBLanes {
+    min: [[f64; BVH_WIDTH]; 3],
+    max: [[f64; BVH_WIDTH]; 3],
+}
+
+impl AABBLanes {
+    // Unused lanes are filled with empty boxes, which can never be hit.
+    pub fn new(boxes: &[AABB]) -> Self {
+        assert!(boxes.len() <= BVH_WIDTH, "Too many boxes for AABBLanes");
+        let mut min = [[f64::INFINITY; BVH_WIDTH]; 3];
+        let mut max = [[f64::NEG_INFINITY; BVH_WIDTH]; 3];
+        for (lane, bbox) in boxes.iter().enumerate() {
+            for axis in 0..3 {
+                let interval = bbox.axis_interval(axis);
+                min[axis][lane] = interval.start;
+                max[axis][lane] = interval.end;
+            }
+        }
+        Self { min, max }
+    }
+
+    // Returns the entry distance of the ray for each lane,
+    // or infinity for lanes whose box is missed.
+    // Follows the same NaN-tolerant slab test as AABB::hit.
+    pub fn hit(&self, ray: &RayLanes, ray_t: Interval) -> [f64; BVH_WIDTH] {
+        let mut t_enter = [ray_t.start; BVH_WIDTH];
+        let mut t_exit = [ray_t.end; BVH_WIDTH];
+
+        for axis in 0..3 {
+            let origin = ray.origin[axis];
+            let inv_dir = ray.inv_dir[axis];
+            let (near, far) = if inv_dir.is_sign_negative() {
+                (&self.max[axis], &self.min[axis])
+            } else {
+                (&self.min[axis], &self.max[axis])
+            };
+            for lane in 0..BVH_WIDTH {
+                let t0 = (near[lane] - origin) * inv_dir;
+                let t1 = (far[lane] - origin) * inv_dir * ROUNDING_PAD;
+                if t0 > t_enter[lane] {
+                    t_enter[lane] = t0;
+                }
+                if t1 < t_exit[lane] {
+                    t_exit[lane] = t1;
+                }
+            }
+        }
+
+        let mut result = [f64::INFINITY; BVH_WIDTH];
+        for lane in 0..BVH_WIDTH {
+            if t_enter[lane] <= t_exit[lane] {
+                result[lane] = t_enter[lane];
+            }
+        }
+        result
+    }
+}
+
+// Per-ray data reused across every node visited during a traversal.
+#[derive(Debug, Clone, Copy)]
+pub struct RayLanes {
+    origin: [f64; 3],
+    inv_dir: [f64; 3],
+}
+
+impl From<&Ray> for RayLanes {
+    fn from(r: &Ray) -> Self {
+        let origin = r.origin();
+        let dir = r.direction();
+        Self {
+            origin: [origin.x, origin.y, origin.z],
+            inv_dir: [1.0 / dir.x, 1.0 / dir.y, 1.0 / dir.z],
+        }
+    }
+}
+
+#[derive(Debug)]
+enum WideChild {
+    Node(Box<WideBVHNode>),
+    Leaf(Rc<dyn Hittable>),
+}
+
+// Bounding Volume Hierarchy with up to BVH_WIDTH children per node.
+// Compared to BVHNode, it is shallower and tests all child boxes of a node in one go.
+#[derive(Debug)]
+pub struct WideBVHNode {
+    children: Vec<WideChild>,
+    child_boxes: AABBLanes,
+    bbox: AABB,
+}
+
+// Unbounded objects are kept, but they make every node above them unbounded as well.
+// Prefer HittableList::into_bvh for lists that may contain them.
+impl From<HittableList> for WideBVHNode {
+    fn from(list: HittableList) -> Self {
+        let objects: Vec<Rc<dyn Hittable>> =
+            list.objects.into_iter().chain(list.unbounded).collect();
+        Self::from(objects.as_slice())
+    }
+}
+
+impl From<&[Rc<dyn Hittable>]> for WideBVHNode {
+    fn from(list: &[Rc<dyn Hittable>]) -> Self {
+        if list.is_empty() {
+            panic!("Empty hittable list passed to WideBVHNode::from()");
+        }
+
+        let mut bbox = EMPTY_AABB;
+        list.iter()
+            .for_each(|h| bbox = AABB::surrounding_box(&h.bounding_box(), &bbox));
+
+        let children: Vec<WideChild> = if list.len() <= BVH_WIDTH {
+            list.iter().map(|h| WideChild::Leaf(h.clone())).collect()
+        } else {
+            // Split the same way BVHNode does, repeatedly, until there are BVH_WIDTH groups.
+            let mut groups = vec![list.to_vec()];
+            while groups.len() < BVH_WIDTH {
+                groups = groups.into_iter().flat_map(Self::split).collect();
+            }
+            groups
+                .into_iter()
+                .map(|group| match group.as_slice() {
+                    [a] => WideChild::Leaf(a.clone()),
+                    group => WideChild::Node(Box::new(WideBVHNode::from(group))),
+                })
+                .collect()
+        };
+
+        let boxes: Vec<AABB> = children.iter().map(WideChild::bounding_box).collect();
+        Self {
+            children,
+            child_boxes: AABBLanes::new(&boxes),
+            bbox,
+        }
+    }
+}
+
+impl WideBVHNode {
+    // Splits the list in half along the longest axis of its bounding box.
+    fn split(mut list: Vec<Rc<dyn Hittable>>) -> Vec<Vec<Rc<dyn Hittable>>> {
+        if list.len() < 2 {
+            return vec![list];
+        }
+        let mut bbox = EMPTY_AABB;
+        list.iter()
+            .for_each(|h| bbox = AABB::surrounding_box(&h.bounding_box(), &bbox));
+
+        let axis = bbox.longest_axis();
+        list.sort_by(|a, b| {
+            a.bounding_box()
+                .axis_interval(axis)
+                .start
+                .partial_cmp(&b.bounding_box().axis_interval(axis).start)
+                .unwrap()
+        });
+        let right = list.split_off(list.len() / 2);
+        vec![list, right]
+    }
+
+    fn hit_lanes(&self, r: &Ray, ray: &RayLanes, mut ray_t: Interval) -> Option<HitRecord> {
+        let t_enter = self.child_boxes.hit(ray, ray_t);
+
+        // visit children front to back, so that farther ones can be culled by closer hits
+        let mut order: [usize; BVH_WIDTH] = std::array::from_fn(|i| i);
+        order.sort_unstable_by(|&a, &b| t_enter[a].total_cmp(&t_enter[b]));
+
+        let mut return_rec: Option<HitRecord> = None;
+        for lane in order {
+            if lane >= self.children.len()
+                || t_enter[lane].is_infinite()
+                || t_enter[lane] > ray_t.end
+            {
+                continue;
+            }
+            let rec = match &self.children[lane] {
+                WideChild::Node(node) => node.hit_lanes(r, ray, ray_t),
+                WideChild::Leaf(object) => object.hit_opaque(r, ray_t),
+            };
+            if let Some(rec) = rec {
+                ray_t.end = rec.t;
+                return_rec = Some(rec);
+            }
+        }
+        return_rec
+    }
+
+    fn transmittance_lanes(&self, r: &Ray, ray: &RayLanes, ray_t: Interval) -> f64 {
+        let t_enter = self.child_boxes.hit(ray, ray_t);
+        let mut transmittance = 1.0;
+        for (lane, child) in self.children.iter().enumerate() {
+            if t_enter[lane].is_infinite() {
+                continue;
+            }
+            transmittance *= match child {
+                WideChild::Node(node) => node.transmittance_lanes(r, ray, ray_t),
+                WideChild::Leaf(object) => object.transmittance(r, ray_t),
+            };
+            if transmittance <= 0.0 {
+                return 0.0;
+            }
+        }
+        transmittance
+    }
+}
+
+impl WideChild {
+    fn bounding_box(&self) -> AABB {
+        match self {
+            WideChild::Node(node) => node.bbox,
+            WideChild::Leaf(object) => object.bounding_box(),
+        }
+    }
+}
+
+impl Hittable for WideBVHNode {
+    fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let ray = RayLanes::from(r);
+        self.hit_lanes(r, &ray, ray_t)
+    }
+
+    fn transmittance(&self, r: &Ray, ray_t: Interval) -> f64 {
+        let ray = RayLanes::from(r);
+        self.transmittance_lanes(r, &ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bbox
+    }
+}
@@ -1 +0,0 @@
-pub mod image_handler;
@@ -1,8 +1,8 @@
 pub mod camera;
 pub mod hittable;
-pub mod imageutil;
+pub mod light;
 pub mod material;
+pub mod mesh;
 pub mod primitive;
 pub mod samples;
 pub mod texture;
-mod utils;
@@ -0,0 +1,160 @@
+// Piecewise constant distributions, used to importance sample tabulated functions.
+
+// A distribution over [0, 1) proportional to a step function with equally wide steps.
+#[derive(Debug, Clone)]
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>, // func.len() + 1 entries, from 0 to 1
+    integral: f64,
+}
+
+impl Distribution1D {
+    pub fn new(func: Vec<f64>) -> Self {
+        assert!(!func.is_empty(), "Distribution1D needs at least one value");
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf.last().unwrap() + f.abs() / n);
+        }
+        let integral = *cdf.last().unwrap();
+        if integral > 0.0 {
+            cdf.iter_mut().for_each(|c| *c /= integral);
+        } else {
+            // nothing to favour, so sample uniformly
+            cdf.iter_mut()
+                .enumerate()
+                .for_each(|(i, c)| *c = i as f64 / n);
+        }
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    // Maps a uniform number in [0, 1) to a sample in [0, 1).
+    // Returns the sample, its probability density, and the index of its step.
+    pub fn sample(&self, xi: f64) -> (f64, f64, usize) {
+        // the last step whose cdf is not above xi
+        let step = self
+            .cdf
+            .partition_point(|&c| c <= xi)
+            .saturating_sub(1)
+            .min(self.func.len() - 1);
+        let width = self.cdf[step + 1] - self.cdf[step];
+        let offset = if width > 0.0 {
+            (xi - self.cdf[step]) / width
+        } else {
+            0.0
+        };
+        let x = ((step as f64 + offset) / self.func.len() as f64).min(1.0 - f64::EPSILON);
+        (x, self.pdf_of_step(step), step)
+    }
+
+    // Probability density of sampling x.
+    pub fn pdf(&self, x: f64) -> f64 {
+        let step = ((x * self.func.len() as f64) as usize).min(self.func.len() - 1);
+        self.pdf_of_step(step)
+    }
+
+    fn pdf_of_step(&self, step: usize) -> f64 {
+        if self.integral > 0.0 {
+            self.func[step].abs() / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+// A distribution over [0, 1)^2, made of a distribution over v (the marginal)
+// and one over u for every step of v (the conditionals).
+#[derive(Debug, Clone)]
+pub struct Distribution2D {
+    conditionals: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    // func holds nu * nv values, u-fastest.
+    pub fn new(func: &[f64], nu: usize, nv: usize) -> Self {
+        assert_eq!(func.len(), nu * nv, "Distribution2D value count mismatch");
+        let conditionals: Vec<Distribution1D> = func
+            .chunks(nu)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(conditionals.iter().map(|d| d.integral()).collect());
+        Self {
+            conditionals,
+            marginal,
+        }
+    }
+
+    // Maps two uniform numbers to a point (u, v), returned with its probability density.
+    pub fn sample(&self, xi: (f64, f64)) -> ((f64, f64), f64) {
+        let (v, pdf_v, row) = self.marginal.sample(xi.1);
+        let (u, pdf_u, _) = self.conditionals[row].sample(xi.0);
+        ((u, v), pdf_u * pdf_v)
+    }
+
+    pub fn pdf(&self, u: f64, v: f64) -> f64 {
+        let row = ((v * self.conditionals.len() as f64) as usize).min(self.conditionals.len() - 1);
+        self.marginal.pdf(v) * self.conditionals[row].pdf(u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn sample_pdf_matches_pdf() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (nu, nv) = (13, 7);
+        // some cells are empty, so that whole steps and rows have zero probability
+        let func: Vec<f64> = (0..nu * nv)
+            .map(|_| {
+                if rng.gen_bool(0.3) {
+                    0.0
+                } else {
+                    rng.gen_range(0.0..10.0)
+                }
+            })
+            .collect();
+        let distribution = Distribution2D::new(&func, nu, nv);
+
+        for _ in 0..10_000 {
+            let ((u, v), pdf) = distribution.sample((rng.gen(), rng.gen()));
+            assert!(
+                (0.0..1.0).contains(&u) && (0.0..1.0).contains(&v),
+                "{u} {v}"
+            );
+            assert!(
+                pdf > 0.0,
+                "sampled a cell that cannot be sampled at {u} {v}"
+            );
+            let expected = distribution.pdf(u, v);
+            assert!(
+                (pdf - expected).abs() <= 1e-9 * expected,
+                "{pdf} vs {expected} at {u} {v}"
+            );
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let func = [1.0, 0.0, 3.0, 2.0, 5.0, 0.5];
+        let distribution = Distribution2D::new(&func, 3, 2);
+        // the pdf is constant over each of the 3 x 2 cells
+        let integral: f64 = (0..2)
+            .flat_map(|j| (0..3).map(move |i| (i, j)))
+            .map(|(i, j)| distribution.pdf((i as f64 + 0.5) / 3.0, (j as f64 + 0.5) / 2.0) / 6.0)
+            .sum();
+        assert!((integral - 1.0).abs() < 1e-12, "{integral}");
+    }
+}
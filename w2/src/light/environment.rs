@@ -0,0 +1,185 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use crate::{
+    primitive::{color::Color, point3::Point3, vec3::Vec3},
+    texture::{
+        image_texture::{ImageTexture, TextureFilter, WrapMode},
+        Texture,
+    },
+};
+
+use super::distribution::Distribution2D;
+
+// Light arriving from infinitely far away in every direction,
+// given by a texture in equirectangular (latitude-longitude) layout:
+// u goes around the y axis and v from straight down (0) to straight up (1),
+// the same mapping as Sphere's texture coordinates.
+// Directions are importance sampled by luminance, so small bright areas such as the sun
+// are found by light samples rather than by chance.
+#[derive(Debug)]
+pub struct EnvironmentLight {
+    texture: Rc<dyn Texture>,
+    intensity: f64,
+    rotation: f64, // around the y axis, in radians
+    distribution: Distribution2D,
+}
+
+impl EnvironmentLight {
+    // `resolution` is the grid the texture is tabulated on for sampling,
+    // usually the size of the image.
+    pub fn new(texture: Rc<dyn Texture>, resolution: (usize, usize), intensity: f64) -> Self {
+        let (nu, nv) = (resolution.0.max(1), resolution.1.max(1));
+        let origin = Point3::zero();
+        let func: Vec<f64> = (0..nv)
+            .flat_map(|j| (0..nu).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                // The brightest of the cell's center and corners, so that cells
+                // only partly covered by a light, such as the rim of the sun, can still be sampled.
+                let brightest = [(0.5, 0.5), (0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+                    .iter()
+                    .map(|(di, dj)| {
+                        let u = (i as f64 + di) / nu as f64;
+                        let v = (j as f64 + dj) / nv as f64;
+                        texture.value(u, v, &origin).luminance().max(0.0)
+                    })
+                    .fold(0.0, f64::max);
+                // rows near the poles cover less solid angle
+                let sin_theta = (PI * (j as f64 + 0.5) / nv as f64).sin();
+                brightest * sin_theta
+            })
+            .collect();
+        Self {
+            texture,
+            intensity,
+            rotation: 0.0,
+            distribution: Distribution2D::new(&func, nu, nv),
+        }
+    }
+
+    // Loads an equirectangular image, typically a Radiance .hdr or OpenEXR file.
+    pub fn from_file(image_path: &str, intensity: f64) -> Result<Self, image::ImageError> {
+        let texture = ImageTexture::new(image_path)?
+            .with_filter(TextureFilter::Bilinear)
+            .with_wrap(WrapMode::Repeat, WrapMode::Clamp);
+        let (width, height) = texture.dimensions();
+        Ok(Self::new(
+            Rc::new(texture),
+            (width as usize, height as usize),
+            intensity,
+        ))
+    }
+
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    // Light arriving along -direction, that is, seen when looking towards direction.
+    pub fn radiance(&self, direction: &Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        self.texture.value(u, v, &Point3::zero()) * self.intensity
+    }
+
+    // Probability density, per solid angle, of sample() returning direction.
+    pub fn pdf(&self, direction: &Vec3) -> f64 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (PI * v).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+
+    // Picks a unit direction towards the environment, in proportion to its brightness.
+    // Returns it with its probability density per solid angle.
+    pub fn sample(&self) -> (Vec3, f64) {
+        let ((u, v), pdf_uv) = self
+            .distribution
+            .sample((rand::random::<f64>(), rand::random::<f64>()));
+        let sin_theta = (PI * v).sin();
+        let direction = self.uv_to_direction(u, v);
+        if sin_theta <= 0.0 {
+            return (direction, 0.0);
+        }
+        // the mapping stretches an area of the unit square over 2 pi^2 sin(theta) steradians
+        (direction, pdf_uv / (2.0 * PI * PI * sin_theta))
+    }
+
+    fn direction_to_uv(&self, direction: &Vec3) -> (f64, f64) {
+        let d = direction.unit();
+        let theta = (-d.y).clamp(-1.0, 1.0).acos();
+        let phi = (-d.z).atan2(d.x) + PI - self.rotation;
+        ((phi / (2.0 * PI)).rem_euclid(1.0), theta / PI)
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let theta = v * PI;
+        let phi = u * 2.0 * PI + self.rotation - PI;
+        // inverse of direction_to_uv: y = -cos(theta), and (x, -z) at angle phi
+        Vec3::new(
+            theta.sin() * phi.cos(),
+            -theta.cos(),
+            -theta.sin() * phi.sin(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // A dim sky with a bright band and a sun, varying in both u and v.
+    #[derive(Debug)]
+    struct Sky;
+
+    impl Texture for Sky {
+        fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+            let sun = if (u - 0.3).hypot(v - 0.7) < 0.05 {
+                50.0
+            } else {
+                0.0
+            };
+            let band = if (0.4..0.5).contains(&v) { 2.0 } else { 0.0 };
+            Color::new(1.0, 1.0, 1.0) * (0.1 + u * v + band + sun)
+        }
+    }
+
+    fn light(rotation: f64) -> EnvironmentLight {
+        EnvironmentLight::new(Rc::new(Sky), (32, 16), 1.0).with_rotation(rotation)
+    }
+
+    #[test]
+    fn uv_and_direction_round_trip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for rotation in [0.0, 1.0, -2.5] {
+            let light = light(rotation);
+            for _ in 0..1000 {
+                let (u, v) = (rng.gen::<f64>(), rng.gen_range(0.001..0.999));
+                let direction = light.uv_to_direction(u, v);
+                assert!((direction.length() - 1.0).abs() < 1e-12);
+                let (u2, v2) = light.direction_to_uv(&direction);
+                // u wraps around
+                let du = (u2 - u).abs();
+                assert!(du.min(1.0 - du) < 1e-9 && (v2 - v).abs() < 1e-9, "{u} {v}");
+
+                let direction = Vec3::random_unit() * rng.gen_range(0.1..10.0);
+                let (u, v) = light.direction_to_uv(&direction);
+                assert!((light.uv_to_direction(u, v) - direction.unit()).length() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn sample_pdf_matches_pdf() {
+        let light = light(0.7);
+        for _ in 0..10_000 {
+            let (direction, pdf) = light.sample();
+            let expected = light.pdf(&direction);
+            assert!(
+                (pdf - expected).abs() <= 1e-6 * expected,
+                "{pdf} vs {expected} along {direction:?}"
+            );
+        }
+    }
+}
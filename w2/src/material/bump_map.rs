@@ -0,0 +1,105 @@
+use std::rc::Rc;
+
+use crate::{
+    hittable::HitRecord,
+    primitive::{color::Color, ray::Ray},
+    texture::Texture,
+};
+
+use super::Material;
+
+// Step in u and v used to take the height texture's derivatives,
+// when the ray does not tell how large a pixel is on the surface.
+const BUMP_DELTA: f64 = 0.0005;
+
+// Wraps a material, perturbing its shading normal as if the surface were displaced
+// along the normal by a height texture (its luminance times `scale`).
+// The geometry itself is unchanged, so silhouettes stay smooth.
+#[derive(Debug)]
+pub struct BumpMap {
+    material: Rc<dyn Material>,
+    height: Rc<dyn Texture>,
+    scale: f64,
+}
+
+impl BumpMap {
+    pub fn new(material: Rc<dyn Material>, height: Rc<dyn Texture>, scale: f64) -> Self {
+        Self {
+            material,
+            height,
+            scale,
+        }
+    }
+}
+
+impl BumpMap {
+    // The hit record with its shading normal bumped, or None where the tangents degenerate.
+    fn bumped(&self, r_in: &Ray, hit_record: &HitRecord) -> Option<HitRecord> {
+        let n = hit_record.shading_normal;
+        let (dpdu, dpdv) = if hit_record.dpdu.near_zero() || hit_record.dpdv.near_zero() {
+            // degenerate parameterization, like at the poles of a sphere
+            (hit_record.shading_basis.u, hit_record.shading_basis.v)
+        } else {
+            (hit_record.dpdu, hit_record.dpdv)
+        };
+
+        // differences over half a pixel, so bumps smaller than that do not alias
+        let (du, dv) = match hit_record.footprint(r_in) {
+            Some(f) => (
+                0.5 * (f.dudx.abs() + f.dudy.abs()),
+                0.5 * (f.dvdx.abs() + f.dvdy.abs()),
+            ),
+            None => (BUMP_DELTA, BUMP_DELTA),
+        };
+        let du = if du > 0.0 { du } else { BUMP_DELTA };
+        let dv = if dv > 0.0 { dv } else { BUMP_DELTA };
+
+        let (u, v, p) = (hit_record.u, hit_record.v, hit_record.p);
+        let height = |u: f64, v: f64, p| self.height.value(u, v, &p).luminance() * self.scale;
+        let h = height(u, v, p);
+        let dhdu = (height(u + du, v, p + dpdu * du) - h) / du;
+        let dhdv = (height(u, v + dv, p + dpdv * dv) - h) / dv;
+
+        // tangents of the displaced surface, whose cross product is its normal
+        let bumped = (dpdu + n * dhdu).cross(&(dpdv + n * dhdv));
+        let unbumped = dpdu.cross(&dpdv);
+        if bumped.near_zero() || unbumped.near_zero() {
+            return None;
+        }
+        // keep the normal on the side the tangents' orientation puts it
+        let bumped = if unbumped.dot(&n) < 0.0 {
+            -bumped
+        } else {
+            bumped
+        };
+        Some(hit_record.clone().with_shading_normal(bumped))
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        match self.bumped(r_in, hit_record) {
+            Some(bumped) => self.material.scatter(r_in, &bumped, attenuation, scattered),
+            None => self
+                .material
+                .scatter(r_in, hit_record, attenuation, scattered),
+        }
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, hit_record: &HitRecord, scattered: &Ray) -> Option<f64> {
+        match self.bumped(r_in, hit_record) {
+            Some(bumped) => self.material.scattering_pdf(r_in, &bumped, scattered),
+            None => self.material.scattering_pdf(r_in, hit_record, scattered),
+        }
+    }
+
+    fn opacity(&self, hit_record: &HitRecord) -> f64 {
+        self.material.opacity(hit_record)
+    }
+}
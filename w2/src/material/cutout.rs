@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use crate::{
+    hittable::HitRecord,
+    primitive::{color::Color, ray::Ray},
+    texture::Texture,
+};
+
+use super::Material;
+
+// Wraps a material, cutting holes in the surface where a mask texture's alpha is below 1,
+// as for leaves, fences and other cards textured with an RGBA image.
+// Where alpha is 0 the surface is not hit at all, so it casts no shadow there either;
+// in between, that fraction of rays goes through.
+#[derive(Debug)]
+pub struct Cutout {
+    material: Rc<dyn Material>,
+    mask: Rc<dyn Texture>,
+}
+
+impl Cutout {
+    pub fn new(material: Rc<dyn Material>, mask: Rc<dyn Texture>) -> Self {
+        Self { material, mask }
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.material
+            .scatter(r_in, hit_record, attenuation, scattered)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, hit_record: &HitRecord, scattered: &Ray) -> Option<f64> {
+        self.material.scattering_pdf(r_in, hit_record, scattered)
+    }
+
+    fn opacity(&self, hit_record: &HitRecord) -> f64 {
+        let alpha = self
+            .mask
+            .alpha(hit_record.u, hit_record.v, &hit_record.p)
+            .clamp(0.0, 1.0);
+        alpha * self.material.opacity(hit_record)
+    }
+}
@@ -44,21 +44,34 @@ impl Material for Dielectric {
         };
 
         let unit_direction = r_in.direction().unit();
-        let cos_theta = (-unit_direction).dot(&hit_record.normal).min(1.0);
+        let normal = hit_record.shading_normal;
+        let cos_theta = (-unit_direction).dot(&normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0; // breaking snell's law
 
-        let direction = if cannot_refract
-            || Dielectric::reflectance(cos_theta, refraction_ratio) > random::<f64>()
-        {
-            // reflected
-            unit_direction.reflect(&hit_record.normal)
+        let reflect = cannot_refract
+            || Dielectric::reflectance(cos_theta, refraction_ratio) > random::<f64>();
+        let direction = if reflect {
+            unit_direction.reflect(&normal)
         } else {
-            // refracted
-            unit_direction.refract(&hit_record.normal, refraction_ratio)
+            unit_direction.refract(&normal, refraction_ratio)
         };
-        *scattered = Ray::new(hit_record.p, direction, r_in.time());
+
+        // carry the pixel footprint through the glass
+        let p = hit_record.p;
+        let differentials =
+            r_in.differentials()
+                .zip(hit_record.footprint(r_in))
+                .map(|(diff, footprint)| {
+                    let (dpdx, dpdy) = (footprint.dpdx, footprint.dpdy);
+                    if reflect {
+                        diff.reflected(p, dpdx, dpdy, &normal)
+                    } else {
+                        diff.refracted(p, dpdx, dpdy, &normal, refraction_ratio)
+                    }
+                });
+        *scattered = Ray::new(p, direction, r_in.time()).with_differentials(differentials);
         true
     }
 }
@@ -0,0 +1,152 @@
+use std::f64::consts::PI;
+
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{
+    hittable::HitRecord,
+    primitive::{color::Color, ray::Ray, vec3::Vec3},
+};
+
+use super::Material;
+
+// Fiber material for hair and fur, after Marschner et al.
+// Light leaving a fiber follows three paths, each sampled as its own lobe:
+// R reflects off the surface, TT passes through the fiber,
+// and TRT bounces once inside and comes back out, picking up the fiber's color twice.
+// Directions are split into a longitudinal angle along the fiber
+// and an azimuthal angle around it, so the material needs the hit's tangent (dpdu).
+#[derive(Debug)]
+pub struct Hair {
+    color: Color,   // absorption color of the fiber, applied on each pass through it
+    roughness: f64, // longitudinal spread of the R lobe, in radians
+    shift: f64,     // tilt of the cuticle scales, in radians
+    refraction_index: f64,
+}
+
+impl Hair {
+    pub fn new(color: Color, roughness: f64, shift: f64, refraction_index: f64) -> Self {
+        Self {
+            color,
+            roughness,
+            shift,
+            refraction_index,
+        }
+    }
+
+    fn reflectance(&self, cosine: f64) -> f64 {
+        // Schlick's approximation, as for Dielectric
+        let r0 = ((1. - self.refraction_index) / (1. + self.refraction_index)).powi(2);
+        r0 + (1. - r0) * (1. - cosine).powi(5)
+    }
+}
+
+// named after the paths in Marschner's paper
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy)]
+enum Lobe {
+    R,
+    TT,
+    TRT,
+}
+
+impl Material for Hair {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        // the fiber's axis runs along u, made perpendicular to the normal
+        let frame = hit_record.shading_basis;
+        let (t, n) = (frame.u, frame.w);
+        let b = t.cross(&n);
+
+        // outgoing direction in fiber coordinates
+        let wo = -r_in.direction().unit();
+        let sin_theta_o = wo.dot(&t).clamp(-1.0, 1.0);
+        let phi_o = wo.dot(&b).atan2(wo.dot(&n));
+
+        let f = self.reflectance(wo.dot(&n).abs());
+        let weights = [
+            (Lobe::R, Color::new(f, f, f)),
+            (Lobe::TT, self.color * ((1.0 - f) * (1.0 - f))),
+            (
+                Lobe::TRT,
+                self.color * self.color * ((1.0 - f) * (1.0 - f) * f),
+            ),
+        ];
+        let total: f64 = weights.iter().map(|(_, w)| w.luminance()).sum();
+        if total <= 0.0 {
+            return false;
+        }
+
+        // pick a lobe in proportion to how much light it carries,
+        // never one that carries none, as its weight is divided by its luminance
+        let mut carrying = weights.iter().filter(|(_, w)| w.luminance() > 0.0);
+        let mut pick = rand::random::<f64>() * total;
+        let mut picked = carrying.next().unwrap();
+        for next in carrying {
+            pick -= picked.1.luminance();
+            if pick <= 0.0 {
+                break;
+            }
+            picked = next;
+        }
+        let (lobe, weight) = *picked;
+        *attenuation = weight * (total / weight.luminance());
+
+        // Longitudinally, every lobe leaves near the mirror direction around the fiber axis,
+        // shifted by the cuticle tilt and blurred by the roughness (Marschner's alpha and beta).
+        let (shift, roughness) = match lobe {
+            Lobe::R => (-2.0 * self.shift, self.roughness),
+            Lobe::TT => (self.shift, 0.5 * self.roughness),
+            Lobe::TRT => (3.0 * self.shift, 2.0 * self.roughness),
+        };
+        let gaussian: f64 = StandardNormal.sample(&mut rand::thread_rng());
+        let theta_i = (-sin_theta_o.asin() + shift + gaussian * roughness).clamp(-PI / 2., PI / 2.);
+
+        // Azimuthally, R mirrors about the normal, TT carries on through the fiber,
+        // and TRT comes back out spread widely around the mirror direction.
+        let phi_i = match lobe {
+            Lobe::R => -phi_o,
+            Lobe::TT => phi_o + PI + (rand::random::<f64>() - 0.5) * PI / 4.,
+            Lobe::TRT => -phi_o + (rand::random::<f64>() - 0.5) * PI,
+        };
+
+        let direction: Vec3 =
+            t * theta_i.sin() + (n * phi_i.cos() + b * phi_i.sin()) * theta_i.cos();
+        *scattered = Ray::new(hit_record.p, direction, r_in.time());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        hittable::{sphere::Sphere, Hittable},
+        primitive::{interval::Interval, point3::Point3},
+    };
+
+    #[test]
+    fn black_fibers_only_reflect() {
+        // only the R lobe carries light, so it's the only one ever picked,
+        // and its gray reflectance is the whole attenuation
+        let hair = Rc::new(Hair::new(Color::new(0.0, 0.0, 0.0), 0.1, 0.05, 1.55));
+        let sphere = Sphere::new(Vec3::zero(), 1.0, hair.clone());
+        let r = Ray::new(Point3::new(0.3, 0.2, 5.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let rec = sphere.hit(&r, Interval::new(0.001, f64::INFINITY)).unwrap();
+
+        let mut attenuation = Color::new(0.0, 0.0, 0.0);
+        let mut scattered = Ray::new(Point3::zero(), Vec3::zero(), 0.0);
+        for _ in 0..1000 {
+            assert!(hair.scatter(&r, &rec, &mut attenuation, &mut scattered));
+            let a = attenuation;
+            assert!(a.r.is_finite() && a.r > 0.0, "{a:?}");
+            assert!(a.r == a.g && a.g == a.b, "{a:?}");
+        }
+    }
+}
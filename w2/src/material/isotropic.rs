@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::{
+    hittable::HitRecord,
+    primitive::{color::Color, ray::Ray, vec3::Vec3},
+    texture::{solid_color::SolidColor, Texture},
+};
+
+use super::Material;
+
+// Phase function of participating media, scattering equally in all directions.
+#[derive(Debug)]
+pub struct Isotropic {
+    texture: Rc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self {
+            texture: Rc::new(SolidColor::new(albedo)),
+        }
+    }
+}
+
+impl From<Rc<dyn Texture>> for Isotropic {
+    fn from(texture: Rc<dyn Texture>) -> Self {
+        Self { texture }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        *scattered = Ray::new(hit_record.p, Vec3::random_unit(), r_in.time());
+        *attenuation = self.texture.value_filtered(
+            hit_record.u,
+            hit_record.v,
+            &hit_record.p,
+            hit_record.footprint(r_in).as_ref(),
+        );
+        true
+    }
+
+    fn scattering_pdf(
+        &self,
+        _r_in: &Ray,
+        _hit_record: &HitRecord,
+        _scattered: &Ray,
+    ) -> Option<f64> {
+        Some(1.0 / (4.0 * std::f64::consts::PI))
+    }
+}
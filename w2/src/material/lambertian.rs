@@ -38,14 +38,29 @@ impl Material for Lambertian {
         // Normal is unit length
         // we choose an arbitrary scatter direction with probability
         // proportional to the cosine of the angle between the normal
-        let mut scatter_direction = hit_record.normal + Vec3::random_unit();
+        let mut scatter_direction = hit_record.shading_normal + Vec3::random_unit();
         if scatter_direction.near_zero() {
-            scatter_direction = hit_record.normal;
+            scatter_direction = hit_record.shading_normal;
         }
         *scattered = Ray::new(hit_record.p, scatter_direction, r_in.time());
-        *attenuation = self
-            .texture
-            .value(hit_record.u, hit_record.v, &hit_record.p);
-        true
+        *attenuation = self.texture.value_filtered(
+            hit_record.u,
+            hit_record.v,
+            &hit_record.p,
+            hit_record.footprint(r_in).as_ref(),
+        );
+
+        // a shading normal tilted away from the geometry can send rays into the actual surface,
+        // which are absorbed
+        scattered.direction().dot(&hit_record.normal) > 0.0
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, hit_record: &HitRecord, scattered: &Ray) -> Option<f64> {
+        if scattered.direction().dot(&hit_record.normal) <= 0.0 {
+            return Some(0.0);
+        }
+        // cosine weighted, as sampled above
+        let cosine = hit_record.shading_normal.dot(&scattered.direction().unit());
+        Some(cosine.max(0.0) / std::f64::consts::PI)
     }
 }
@@ -28,11 +28,21 @@ impl Material for Metal {
         attenuation: &mut Color,
         scattered: &mut Ray,
     ) -> bool {
-        let mut reflected: Vec3 = r_in.direction().reflect(&hit_record.normal);
+        let normal = hit_record.shading_normal;
+        let mut reflected: Vec3 = r_in.direction().reflect(&normal);
         reflected = reflected.unit() + Vec3::random_unit() * self.fuzz;
-        *scattered = Ray::new(hit_record.p, reflected, r_in.time());
+        // only perfect mirrors keep the pixel footprint, fuzzy reflections blur it anyway
+        let differentials = match (r_in.differentials(), hit_record.footprint(r_in)) {
+            (Some(diff), Some(footprint)) if self.fuzz == 0.0 => {
+                Some(diff.reflected(hit_record.p, footprint.dpdx, footprint.dpdy, &normal))
+            }
+            _ => None,
+        };
+        *scattered =
+            Ray::new(hit_record.p, reflected, r_in.time()).with_differentials(differentials);
         *attenuation = self.albedo;
 
+        // rays reflected into the actual surface are absorbed
         scattered.direction().dot(&hit_record.normal) > 0.0
     }
 }
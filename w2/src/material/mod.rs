@@ -11,8 +11,33 @@ pub trait Material: std::fmt::Debug {
         attenuation: &mut Color,
         scattered: &mut Ray,
     ) -> bool;
+
+    // Probability density, per solid angle, of scatter() choosing the direction of `scattered`.
+    // For materials that sample directions in proportion to the light they scatter,
+    // attenuation * pdf is their response to light arriving from that direction,
+    // which lets the camera sample light sources directly.
+    // Mirror-like materials, and those that cannot tell, return None.
+    fn scattering_pdf(
+        &self,
+        _r_in: &Ray,
+        _hit_record: &HitRecord,
+        _scattered: &Ray,
+    ) -> Option<f64> {
+        None
+    }
+
+    // Coverage of the surface at the hit, from 0 to 1.
+    // Rays pass through the uncovered fraction without scattering, see HitRecord::is_cut_out.
+    fn opacity(&self, _hit_record: &HitRecord) -> f64 {
+        1.0
+    }
 }
 
+pub mod bump_map;
+pub mod cutout;
 pub mod dielectric;
+pub mod hair;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod normal_map;
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use crate::{
+    hittable::HitRecord,
+    primitive::{color::Color, ray::Ray, vec3::Vec3},
+    texture::Texture,
+};
+
+use super::Material;
+
+// Wraps a material, replacing its shading normal with one read from a tangent space normal map:
+// red, green and blue map to the directions of increasing u, increasing v, and the normal,
+// from [0, 1] to [-1, 1].
+// The texture must hold raw data, see ImageTexture::new_data.
+// `strength` scales the tilt away from the unperturbed normal.
+#[derive(Debug)]
+pub struct NormalMap {
+    material: Rc<dyn Material>,
+    normals: Rc<dyn Texture>,
+    strength: f64,
+}
+
+impl NormalMap {
+    pub fn new(material: Rc<dyn Material>, normals: Rc<dyn Texture>, strength: f64) -> Self {
+        Self {
+            material,
+            normals,
+            strength,
+        }
+    }
+}
+
+impl NormalMap {
+    // The hit record with the shading normal read from the map.
+    fn mapped(&self, hit_record: &HitRecord) -> HitRecord {
+        let frame = hit_record.shading_basis;
+        // the map's green channel follows v, whichever way the basis is turned
+        let bitangent = if frame.v.dot(&hit_record.dpdv) < 0.0 {
+            -frame.v
+        } else {
+            frame.v
+        };
+
+        let c = self
+            .normals
+            .value(hit_record.u, hit_record.v, &hit_record.p);
+        let local = Vec3::new(
+            (2.0 * c.r - 1.0) * self.strength,
+            (2.0 * c.g - 1.0) * self.strength,
+            2.0 * c.b - 1.0,
+        );
+        let mapped = frame.u * local.x + bitangent * local.y + frame.w * local.z.max(1e-3);
+        hit_record.clone().with_shading_normal(mapped)
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.material
+            .scatter(r_in, &self.mapped(hit_record), attenuation, scattered)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, hit_record: &HitRecord, scattered: &Ray) -> Option<f64> {
+        self.material
+            .scattering_pdf(r_in, &self.mapped(hit_record), scattered)
+    }
+
+    fn opacity(&self, hit_record: &HitRecord) -> f64 {
+        self.material.opacity(hit_record)
+    }
+}
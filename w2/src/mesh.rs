@@ -0,0 +1,315 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    hittable::{hittable_list::HittableList, triangle::Triangle, Hittable},
+    material::Material,
+    primitive::{point3::Point3, vec3::Vec3},
+};
+
+// A polygon mesh with shared vertices, used to process geometry before rendering.
+// Faces list vertex indices in counter-clockwise order seen from the outside,
+// and may have any number of sides.
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub positions: Vec<Point3>,
+    pub uvs: Option<Vec<(f64, f64)>>, // one per vertex
+    pub faces: Vec<Vec<usize>>,
+    // edges that stay sharp when the mesh is refined, stored with the smaller index first
+    pub creases: HashSet<(usize, usize)>,
+}
+
+impl Mesh {
+    pub fn new(positions: Vec<Point3>, faces: Vec<Vec<usize>>) -> Self {
+        Self {
+            positions,
+            uvs: None,
+            faces,
+            creases: HashSet::new(),
+        }
+    }
+
+    // An axis aligned cube of six quads, without uvs.
+    // Vertex i is at the corner with positive x, y and z where bits 0, 1 and 2 of i are set.
+    pub fn cube(center: Point3, half_size: f64) -> Self {
+        let positions = (0..8)
+            .map(|i| {
+                let corner = |bit: usize| if i & bit != 0 { half_size } else { -half_size };
+                center + Vec3::new(corner(1), corner(2), corner(4))
+            })
+            .collect();
+        let faces = vec![
+            vec![0, 2, 3, 1], // -z
+            vec![4, 5, 7, 6], // +z
+            vec![0, 4, 6, 2], // -x
+            vec![1, 3, 7, 5], // +x
+            vec![0, 1, 5, 4], // -y
+            vec![2, 6, 7, 3], // +y
+        ];
+        Self::new(positions, faces)
+    }
+
+    pub fn with_uvs(mut self, uvs: Vec<(f64, f64)>) -> Self {
+        assert_eq!(
+            uvs.len(),
+            self.positions.len(),
+            "Mesh needs one uv per vertex"
+        );
+        self.uvs = Some(uvs);
+        self
+    }
+
+    pub fn add_crease(&mut self, a: usize, b: usize) {
+        self.creases.insert(edge_key(a, b));
+    }
+
+    pub fn is_crease(&self, a: usize, b: usize) -> bool {
+        self.creases.contains(&edge_key(a, b))
+    }
+
+    // Maps every edge to the faces containing it.
+    pub fn edge_faces(&self) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            for (a, b) in face_edges(face) {
+                edges.entry(edge_key(a, b)).or_default().push(f);
+            }
+        }
+        edges
+    }
+
+    // Area weighted vertex normals, smooth across creases,
+    // so that moving vertices along them does not tear the mesh apart.
+    pub fn vertex_normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::zero(); self.positions.len()];
+        for face in &self.faces {
+            let n = self.face_normal(face);
+            for &i in face {
+                normals[i] = normals[i] + n;
+            }
+        }
+        normals
+            .into_iter()
+            .map(|n| if n.near_zero() { n } else { n.unit() })
+            .collect()
+    }
+
+    // Area weighted normals for every corner of every face, in the same layout as faces.
+    // Around each vertex, faces joined by an edge that is not a crease share their normal,
+    // while the faces on either side of a crease are shaded separately, so creases look sharp.
+    pub fn corner_normals(&self) -> Vec<Vec<Vec3>> {
+        // corners are numbered face by face
+        let mut first_corner = Vec::with_capacity(self.faces.len());
+        let mut corner_count = 0;
+        for face in &self.faces {
+            first_corner.push(corner_count);
+            corner_count += face.len();
+        }
+        let corner = |f: usize, vertex: usize| {
+            let i = self.faces[f].iter().position(|&v| v == vertex).unwrap();
+            first_corner[f] + i
+        };
+
+        // join the corners of a vertex across every smooth edge around it
+        let mut groups = DisjointSets::new(corner_count);
+        for (&(a, b), faces) in &self.edge_faces() {
+            if self.creases.contains(&(a, b)) {
+                continue;
+            }
+            for pair in faces.windows(2) {
+                for vertex in [a, b] {
+                    groups.union(corner(pair[0], vertex), corner(pair[1], vertex));
+                }
+            }
+        }
+
+        let mut sums = vec![Vec3::zero(); corner_count];
+        for (f, face) in self.faces.iter().enumerate() {
+            let n = self.face_normal(face);
+            for i in 0..face.len() {
+                let root = groups.find(first_corner[f] + i);
+                sums[root] = sums[root] + n;
+            }
+        }
+        self.faces
+            .iter()
+            .enumerate()
+            .map(|(f, face)| {
+                (0..face.len())
+                    .map(|i| {
+                        let n = sums[groups.find(first_corner[f] + i)];
+                        if n.near_zero() {
+                            n
+                        } else {
+                            n.unit()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Normal of a face, with a length of twice its area, summed over its fan of triangles.
+    fn face_normal(&self, face: &[usize]) -> Vec3 {
+        (1..face.len().saturating_sub(1))
+            .map(|i| {
+                let [a, b, c] = [face[0], face[i], face[i + 1]].map(|i| self.positions[i]);
+                (b - a).cross(&(c - a))
+            })
+            .fold(Vec3::zero(), |sum, n| sum + n)
+    }
+
+    // Splits every face into a fan of triangles.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        self.faces
+            .iter()
+            .flat_map(|face| {
+                (1..face.len().saturating_sub(1)).map(|i| [face[0], face[i], face[i + 1]])
+            })
+            .collect()
+    }
+
+    // Converts the mesh into triangles with interpolated corner normals,
+    // ready to be put into a BVH.
+    pub fn triangles(&self, material: Rc<dyn Material>) -> Vec<Rc<dyn Hittable>> {
+        let normals = self.corner_normals();
+        self.faces
+            .iter()
+            .zip(normals)
+            .flat_map(|(face, normals)| {
+                let material = material.clone();
+                // a fan of triangles, as in triangulate, keeping track of the face's corners
+                (1..face.len().saturating_sub(1)).map(move |i| {
+                    let corners = [0, i, i + 1];
+                    let [a, b, c] = corners.map(|k| face[k]);
+                    let mut triangle = Triangle::new(
+                        self.positions[a],
+                        self.positions[b],
+                        self.positions[c],
+                        material.clone(),
+                    );
+                    if corners.iter().all(|&k| !normals[k].near_zero()) {
+                        triangle = triangle.with_normals(corners.map(|k| normals[k]));
+                    }
+                    if let Some(uvs) = &self.uvs {
+                        triangle = triangle.with_uvs([uvs[a], uvs[b], uvs[c]]);
+                    }
+                    Rc::new(triangle) as Rc<dyn Hittable>
+                })
+            })
+            .collect()
+    }
+
+    pub fn to_hittable_list(&self, material: Rc<dyn Material>) -> HittableList {
+        let mut list = HittableList::new();
+        self.triangles(material)
+            .into_iter()
+            .for_each(|triangle| list.add(triangle));
+        list
+    }
+}
+
+// Union-find over 0..n, to group the corners that share a normal.
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
+}
+
+pub(crate) fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+// consecutive vertex pairs of a face, wrapping around
+pub(crate) fn face_edges(face: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..face.len()).map(move |i| (face[i], face[(i + 1) % face.len()]))
+}
+
+pub mod displacement;
+pub mod subdivision;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{a:?} vs {b:?}");
+    }
+
+    // The normal of face f at vertex.
+    fn corner_normal(mesh: &Mesh, normals: &[Vec<Vec3>], f: usize, vertex: usize) -> Vec3 {
+        let i = mesh.faces[f].iter().position(|&v| v == vertex).unwrap();
+        normals[f][i]
+    }
+
+    #[test]
+    fn smooth_corners_share_the_vertex_normal() {
+        let mesh = Mesh::cube(Point3::zero(), 1.0);
+        let normals = mesh.corner_normals();
+        let vertex_normals = mesh.vertex_normals();
+        for (f, face) in mesh.faces.iter().enumerate() {
+            for (i, &v) in face.iter().enumerate() {
+                assert_near(normals[f][i], vertex_normals[v]);
+            }
+        }
+        // vertex 2 is the corner at (-1, 1, -1)
+        assert_near(vertex_normals[2], Vec3::new(-1.0, 1.0, -1.0).unit());
+    }
+
+    #[test]
+    fn creases_split_the_normals() {
+        let mut mesh = Mesh::cube(Point3::zero(), 1.0);
+        // the edges around the top face
+        for (a, b) in [(2, 6), (6, 7), (7, 3), (3, 2)] {
+            mesh.add_crease(a, b);
+        }
+        let normals = mesh.corner_normals();
+        // the top face is flat
+        for n in &normals[5] {
+            assert_near(*n, Vec3::new(0.0, 1.0, 0.0));
+        }
+        // the sides stay smooth around the vertical edges
+        let side = Vec3::new(-1.0, 0.0, -1.0).unit();
+        assert_near(corner_normal(&mesh, &normals, 0, 2), side);
+        assert_near(corner_normal(&mesh, &normals, 2, 2), side);
+        assert_near(
+            corner_normal(&mesh, &normals, 0, 0),
+            Vec3::new(-1.0, -1.0, -1.0).unit(),
+        );
+
+        // with every edge creased, the cube is shaded flat
+        for face in mesh.faces.clone() {
+            for (a, b) in face_edges(&face) {
+                mesh.add_crease(a, b);
+            }
+        }
+        let normals = mesh.corner_normals();
+        for (f, face) in mesh.faces.iter().enumerate() {
+            let flat = mesh.face_normal(face).unit();
+            for n in &normals[f] {
+                assert_near(*n, flat);
+            }
+        }
+    }
+}
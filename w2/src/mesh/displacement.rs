@@ -0,0 +1,82 @@
+use crate::{primitive::point3::Point3, texture::Texture};
+
+use super::{subdivision::SubdivisionScheme, Mesh};
+
+// Each level multiplies the number of faces by 4, so this allows up to 4^8 = 65536 times as many.
+const MAX_TESSELLATION_LEVELS: usize = 8;
+
+impl Mesh {
+    pub fn max_edge_length(&self) -> f64 {
+        self.edge_faces()
+            .keys()
+            .map(|&(a, b)| (self.positions[a] - self.positions[b]).length())
+            .fold(0.0, f64::max)
+    }
+
+    // Splits triangles at their edge midpoints until no edge is longer than max_edge_length,
+    // or MAX_TESSELLATION_LEVELS splits have been made.
+    // The whole mesh is refined uniformly, so that no cracks open between neighbouring faces.
+    pub fn tessellate(&self, max_edge_length: f64) -> Mesh {
+        assert!(
+            max_edge_length > 0.0,
+            "tessellate needs a positive max_edge_length, got {max_edge_length}"
+        );
+        let mut mesh = self.subdivide(SubdivisionScheme::Linear, 0);
+        for _ in 0..MAX_TESSELLATION_LEVELS {
+            if mesh.max_edge_length() <= max_edge_length {
+                break;
+            }
+            mesh = mesh.subdivide(SubdivisionScheme::Linear, 1);
+        }
+        mesh
+    }
+
+    // Moves every vertex along its normal by (luminance(texture) - midlevel) * scale.
+    // The texture is looked up at the vertex's uv (or (0, 0) without uvs) and position,
+    // so tessellate first for the displacement to show detail between the original vertices.
+    pub fn displace(&self, texture: &dyn Texture, scale: f64, midlevel: f64) -> Mesh {
+        let normals = self.vertex_normals();
+        let positions: Vec<Point3> = self
+            .positions
+            .iter()
+            .zip(normals)
+            .enumerate()
+            .map(|(i, (p, n))| {
+                let (u, v) = self.uvs.as_ref().map_or((0.0, 0.0), |uvs| uvs[i]);
+                let height = texture.value(u, v, p).luminance() - midlevel;
+                *p + n * (height * scale)
+            })
+            .collect();
+        Mesh {
+            positions,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tessellate_splits_down_to_the_edge_length() {
+        let mesh = Mesh::cube(Point3::zero(), 1.0);
+        // the face diagonals are the longest edges, 2 * sqrt(2) long
+        assert_eq!(mesh.tessellate(f64::INFINITY).faces.len(), 12);
+        let fine = mesh.tessellate(1.0);
+        assert!(fine.max_edge_length() <= 1.0);
+        assert_eq!(fine.faces.len(), 12 * 4 * 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tessellate_rejects_a_zero_edge_length() {
+        Mesh::cube(Point3::zero(), 1.0).tessellate(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tessellate_rejects_nan() {
+        Mesh::cube(Point3::zero(), 1.0).tessellate(f64::NAN);
+    }
+}
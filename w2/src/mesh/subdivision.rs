@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::primitive::point3::Point3;
+
+use super::{edge_key, Mesh};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubdivisionScheme {
+    // For triangle meshes. Other faces are triangulated first.
+    Loop,
+    // For quad meshes, but works on any polygon. Produces quads only.
+    CatmullClark,
+    // Splits every triangle into four at its edge midpoints, without smoothing.
+    // Other faces are triangulated first.
+    Linear,
+}
+
+// A new vertex, as a weighted sum of vertices of the coarser mesh.
+// Weights may repeat an index, they are simply added up.
+type Stencil = Vec<(usize, f64)>;
+
+impl Mesh {
+    // Refines the mesh `levels` times.
+    // Edges marked as creases, and boundary edges, are kept sharp:
+    // they are subdivided as curves of their own, without pulling in the surrounding faces.
+    // UVs are refined with the same rules as positions.
+    pub fn subdivide(&self, scheme: SubdivisionScheme, levels: u32) -> Mesh {
+        let mut mesh = match scheme {
+            SubdivisionScheme::Loop | SubdivisionScheme::Linear => self.triangulated(),
+            SubdivisionScheme::CatmullClark => self.clone(),
+        };
+        for _ in 0..levels {
+            mesh = mesh.subdivide_once(scheme);
+        }
+        mesh
+    }
+
+    fn triangulated(&self) -> Mesh {
+        Mesh {
+            faces: self.triangulate().into_iter().map(Vec::from).collect(),
+            ..self.clone()
+        }
+    }
+
+    fn subdivide_once(&self, scheme: SubdivisionScheme) -> Mesh {
+        let edge_faces = self.edge_faces();
+        let is_sharp = |edge: &(usize, usize)| {
+            self.creases.contains(edge) || edge_faces.get(edge).is_none_or(|f| f.len() != 2)
+        };
+
+        // neighbours of every vertex, and those connected through sharp edges
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.positions.len()];
+        let mut sharp_neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.positions.len()];
+        let mut edges: Vec<(usize, usize)> = edge_faces.keys().cloned().collect();
+        edges.sort_unstable();
+        for edge in &edges {
+            let (a, b) = *edge;
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+            if is_sharp(edge) {
+                sharp_neighbours[a].push(b);
+                sharp_neighbours[b].push(a);
+            }
+        }
+
+        let mut faces_of_vertex: Vec<Vec<usize>> = vec![Vec::new(); self.positions.len()];
+        for (f, face) in self.faces.iter().enumerate() {
+            face.iter().for_each(|&v| faces_of_vertex[v].push(f));
+        }
+
+        let face_point = |f: usize| -> Stencil {
+            let face = &self.faces[f];
+            let w = 1.0 / face.len() as f64;
+            face.iter().map(|&v| (v, w)).collect()
+        };
+
+        // vertex points, replacing the old vertices
+        let mut stencils: Vec<Stencil> = (0..self.positions.len())
+            .map(|v| match sharp_neighbours[v].as_slice() {
+                _ if scheme == SubdivisionScheme::Linear => vec![(v, 1.0)],
+                [a, b] => vec![(v, 0.75), (*a, 0.125), (*b, 0.125)],
+                s if s.len() > 2 => vec![(v, 1.0)], // corner
+                _ if neighbours[v].is_empty() => vec![(v, 1.0)],
+                _ => match scheme {
+                    SubdivisionScheme::Loop => Self::loop_vertex(v, &neighbours[v]),
+                    SubdivisionScheme::Linear => unreachable!(),
+                    SubdivisionScheme::CatmullClark => {
+                        let n = neighbours[v].len() as f64;
+                        let faces = &faces_of_vertex[v];
+                        // (F + 2R + (n - 3)P) / n, with F the average face point,
+                        // R the average edge midpoint and P the vertex itself
+                        let mut stencil: Stencil = vec![(v, (n - 3.0) / n)];
+                        for &f in faces {
+                            for (i, w) in face_point(f) {
+                                stencil.push((i, w / (faces.len() as f64 * n)));
+                            }
+                        }
+                        for &nb in &neighbours[v] {
+                            stencil.push((v, 1.0 / (n * n)));
+                            stencil.push((nb, 1.0 / (n * n)));
+                        }
+                        stencil
+                    }
+                },
+            })
+            .collect();
+
+        // edge points
+        let mut edge_point: HashMap<(usize, usize), usize> = HashMap::new();
+        for edge in &edges {
+            let (a, b) = *edge;
+            edge_point.insert(*edge, stencils.len());
+            let stencil = match scheme {
+                _ if is_sharp(edge) => vec![(a, 0.5), (b, 0.5)],
+                SubdivisionScheme::Linear => vec![(a, 0.5), (b, 0.5)],
+                SubdivisionScheme::Loop => {
+                    let mut stencil = vec![(a, 0.375), (b, 0.375)];
+                    for &f in &edge_faces[edge] {
+                        let opposite = self.faces[f].iter().find(|&&v| v != a && v != b);
+                        if let Some(&c) = opposite {
+                            stencil.push((c, 0.125));
+                        }
+                    }
+                    stencil
+                }
+                SubdivisionScheme::CatmullClark => {
+                    let mut stencil = vec![(a, 0.25), (b, 0.25)];
+                    for &f in &edge_faces[edge] {
+                        stencil.extend(face_point(f).into_iter().map(|(i, w)| (i, w * 0.25)));
+                    }
+                    stencil
+                }
+            };
+            stencils.push(stencil);
+        }
+
+        let mut creases = HashSet::new();
+        for &(a, b) in &self.creases {
+            if let Some(&e) = edge_point.get(&(a, b)) {
+                creases.insert(edge_key(a, e));
+                creases.insert(edge_key(e, b));
+            }
+        }
+
+        let e = |a: usize, b: usize| edge_point[&edge_key(a, b)];
+        let faces: Vec<Vec<usize>> = match scheme {
+            SubdivisionScheme::Loop | SubdivisionScheme::Linear => self
+                .faces
+                .iter()
+                .flat_map(|face| {
+                    let (a, b, c) = (face[0], face[1], face[2]);
+                    let (ab, bc, ca) = (e(a, b), e(b, c), e(c, a));
+                    [
+                        vec![a, ab, ca],
+                        vec![b, bc, ab],
+                        vec![c, ca, bc],
+                        vec![ab, bc, ca],
+                    ]
+                })
+                .collect(),
+            SubdivisionScheme::CatmullClark => {
+                let mut faces = Vec::new();
+                for (f, face) in self.faces.iter().enumerate() {
+                    let center = stencils.len();
+                    stencils.push(face_point(f));
+                    let k = face.len();
+                    for i in 0..k {
+                        let prev = face[(i + k - 1) % k];
+                        let next = face[(i + 1) % k];
+                        faces.push(vec![face[i], e(face[i], next), center, e(prev, face[i])]);
+                    }
+                }
+                faces
+            }
+        };
+
+        let positions = stencils
+            .iter()
+            .map(|stencil| {
+                stencil
+                    .iter()
+                    .fold(Point3::zero(), |acc, &(i, w)| acc + self.positions[i] * w)
+            })
+            .collect();
+        let uvs = self.uvs.as_ref().map(|uvs| {
+            stencils
+                .iter()
+                .map(|stencil| {
+                    stencil.iter().fold((0.0, 0.0), |acc, &(i, w)| {
+                        (acc.0 + uvs[i].0 * w, acc.1 + uvs[i].1 * w)
+                    })
+                })
+                .collect()
+        });
+
+        Mesh {
+            positions,
+            uvs,
+            faces,
+            creases,
+        }
+    }
+
+    // Loop's smooth vertex rule, with Warren's weights
+    fn loop_vertex(v: usize, neighbours: &[usize]) -> Stencil {
+        let n = neighbours.len();
+        let beta = if n == 3 {
+            3.0 / 16.0
+        } else {
+            3.0 / (8.0 * n as f64)
+        };
+        let mut stencil = vec![(v, 1.0 - n as f64 * beta)];
+        stencil.extend(neighbours.iter().map(|&nb| (nb, beta)));
+        stencil
+    }
+}
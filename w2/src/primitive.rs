@@ -1,5 +1,6 @@
 pub mod color;
 pub mod interval;
+pub mod onb;
 pub mod point3;
 pub mod ray;
 pub mod vec3;
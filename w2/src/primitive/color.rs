@@ -43,6 +43,10 @@ impl Color {
             (256.0 * INTENSITY.clamp(color.b)) as u8
         )
     }
+    // Perceived brightness of a linear color, with Rec. 709 weights.
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
     pub fn random() -> Self {
         Self {
             r: random(),
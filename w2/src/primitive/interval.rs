@@ -25,6 +25,13 @@ impl Interval {
             end: self.end.max(other.end),
         }
     }
+    // returns the overlap of both intervals, which is empty if they don't overlap.
+    pub fn intersect(&self, other: &Interval) -> Interval {
+        Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        }
+    }
     pub fn size(&self) -> f64 {
         self.end - self.start
     }
@@ -56,3 +63,7 @@ pub static MAX_INTERVAL: Interval = Interval {
     start: f64::MIN,
     end: f64::MAX,
 };
+pub static UNIVERSE_INTERVAL: Interval = Interval {
+    start: f64::NEG_INFINITY,
+    end: f64::INFINITY,
+};
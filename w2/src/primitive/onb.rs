@@ -0,0 +1,51 @@
+use super::vec3::Vec3;
+
+// Orthonormal basis, with w as the principal axis.
+// The basis is right-handed: u x v = w.
+#[derive(Debug, Clone, Copy)]
+pub struct ONB {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl ONB {
+    // Builds a basis around n, which does not need to be unit length.
+    pub fn new(n: &Vec3) -> Self {
+        let w = n.unit();
+        let a = if w.x.abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(&a).unit();
+        let u = v.cross(&w);
+        Self { u, v, w }
+    }
+
+    // Builds a basis around n, with u along the part of `tangent` perpendicular to n.
+    // Falls back to an arbitrary u when tangent is zero or parallel to n.
+    pub fn from_normal_tangent(n: &Vec3, tangent: &Vec3) -> Self {
+        let w = n.unit();
+        let u = *tangent - w * w.dot(tangent);
+        if u.near_zero() {
+            return Self::new(n);
+        }
+        let u = u.unit();
+        Self {
+            u,
+            v: w.cross(&u),
+            w,
+        }
+    }
+
+    // Transforms a vector expressed in this basis to world space.
+    pub fn local_to_world(&self, a: &Vec3) -> Vec3 {
+        self.u * a.x + self.v * a.y + self.w * a.z
+    }
+
+    // Expresses a world space vector in this basis.
+    pub fn world_to_local(&self, a: &Vec3) -> Vec3 {
+        Vec3::new(a.dot(&self.u), a.dot(&self.v), a.dot(&self.w))
+    }
+}
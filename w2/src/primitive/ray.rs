@@ -5,6 +5,18 @@ pub struct Ray {
     origin: Point3,
     direction: Vec3,
     time: f64,
+    differentials: Option<RayDifferentials>,
+}
+
+// Offset rays through the neighbouring pixels, one step along x and along y in the image.
+// They follow the main ray through mirror-like bounces,
+// and tell how large an area of a surface one pixel covers.
+#[derive(Debug, Clone, Copy)]
+pub struct RayDifferentials {
+    pub rx_origin: Point3,
+    pub rx_direction: Vec3,
+    pub ry_origin: Point3,
+    pub ry_direction: Vec3,
 }
 
 impl Ray {
@@ -13,9 +25,15 @@ impl Ray {
             origin,
             direction,
             time,
+            differentials: None,
         }
     }
 
+    pub fn with_differentials(mut self, differentials: Option<RayDifferentials>) -> Self {
+        self.differentials = differentials;
+        self
+    }
+
     pub fn origin(&self) -> Point3 {
         self.origin
     }
@@ -25,7 +43,51 @@ impl Ray {
     pub fn time(&self) -> f64 {
         self.time
     }
+    pub fn differentials(&self) -> Option<&RayDifferentials> {
+        self.differentials.as_ref()
+    }
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + self.direction * t
     }
 }
+
+impl RayDifferentials {
+    // Scales the offsets, for example to account for several samples per pixel.
+    pub fn scaled(&self, origin: Point3, direction: Vec3, scale: f64) -> Self {
+        Self {
+            rx_origin: origin + (self.rx_origin - origin) * scale,
+            rx_direction: direction + (self.rx_direction - direction) * scale,
+            ry_origin: origin + (self.ry_origin - origin) * scale,
+            ry_direction: direction + (self.ry_direction - direction) * scale,
+        }
+    }
+
+    // Offset rays after a mirror reflection at p about normal n,
+    // given where they hit the surface relative to p (dpdx and dpdy).
+    // The curvature of the surface is ignored.
+    pub fn reflected(&self, p: Point3, dpdx: Vec3, dpdy: Vec3, n: &Vec3) -> Self {
+        Self {
+            rx_origin: p + dpdx,
+            rx_direction: self.rx_direction.reflect(n),
+            ry_origin: p + dpdy,
+            ry_direction: self.ry_direction.reflect(n),
+        }
+    }
+
+    // Offset rays after refraction at p, as for reflected.
+    pub fn refracted(
+        &self,
+        p: Point3,
+        dpdx: Vec3,
+        dpdy: Vec3,
+        n: &Vec3,
+        etai_over_etat: f64,
+    ) -> Self {
+        Self {
+            rx_origin: p + dpdx,
+            rx_direction: self.rx_direction.unit().refract(n, etai_over_etat),
+            ry_origin: p + dpdy,
+            ry_direction: self.ry_direction.unit().refract(n, etai_over_etat),
+        }
+    }
+}
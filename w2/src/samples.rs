@@ -1,5 +1,21 @@
 // Samples contains sample scenes for the ray tracer.
 pub mod bouncing_spheres;
+pub mod bump_mapping;
+pub mod cellular;
 pub mod checkered_spheres;
+pub mod cloud;
+pub mod csg_shapes;
+pub mod cutout;
+pub mod displaced_rocks;
 pub mod earth;
+pub mod environment_light;
+pub mod furball;
+pub mod material_library;
+pub mod noise_patterns;
 pub mod perlin_spheres;
+pub mod primitives;
+pub mod sdf_shapes;
+pub mod smoke_spheres;
+pub mod subdivision_shapes;
+pub mod terrain;
+pub mod texture_graph;
@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use crate::{
     camera::CameraOptionsBuilder,
-    hittable::{bvh::BVHNode, hittable_list::HittableList, sphere::Sphere},
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
     material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal, Material},
     primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
     texture::{checker_texture::CheckerTexture, solid_color::SolidColor, Texture},
@@ -21,9 +21,9 @@ pub fn bouncing_spheres(out: &mut impl std::io::Write) -> std::io::Result<()> {
 
     let lambert_ground = Rc::new(Lambertian::from(checker as Rc<dyn Texture>));
 
-    world.add(Rc::new(Sphere::new(
-        Vec3::new(0.0, -1000.0, 0.0),
-        1000.0,
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
         lambert_ground.clone(),
     )));
 
@@ -81,10 +81,7 @@ pub fn bouncing_spheres(out: &mut impl std::io::Write) -> std::io::Result<()> {
     )));
 
     // convert world to bvh
-    let bvh_node = Rc::new(BVHNode::from(world));
-
-    world = HittableList::new();
-    world.add(bvh_node);
+    world = world.into_bvh();
 
     let opts = CameraOptionsBuilder::default()
         .aspect_ratio(16. / 9.)
@@ -0,0 +1,67 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::{
+        bump_map::BumpMap, dielectric::Dielectric, lambertian::Lambertian, metal::Metal, Material,
+    },
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{noise_texture::NoiseTexture, Texture},
+};
+
+pub fn bump_mapping(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    let marble: Rc<dyn Texture> = Rc::new(NoiseTexture::new(4.));
+    let ripples: Rc<dyn Texture> = Rc::new(NoiseTexture::new(1.));
+
+    // the floor is flat, its ripples only come from the shading normal
+    let floor: Rc<dyn Material> = Rc::new(BumpMap::new(
+        Rc::new(Lambertian::new(Color::new(0.6, 0.6, 0.6))),
+        ripples,
+        0.1,
+    ));
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        floor,
+    )));
+
+    let bumpy = |material: Rc<dyn Material>| -> Rc<dyn Material> {
+        Rc::new(BumpMap::new(material, marble.clone(), 0.05))
+    };
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.2, 1.0, 0.0),
+        1.0,
+        bumpy(Rc::new(Lambertian::new(Color::new(0.7, 0.3, 0.2)))),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        bumpy(Rc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.0))),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.2, 1.0, 0.0),
+        1.0,
+        bumpy(Rc::new(Dielectric::new(1.5))),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 3., 9.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::lambertian::Lambertian,
+    primitive::{interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{
+        worley::DistanceMetric,
+        worley_texture::{CellularFeature, WorleyTexture},
+        Texture,
+    },
+};
+
+pub fn cellular(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    let voronoi: Rc<dyn Texture> = Rc::new(
+        WorleyTexture::new(1.)
+            .with_seed(3)
+            .with_feature(CellularFeature::Cell),
+    );
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::from(voronoi)),
+    )));
+
+    // one row per metric, one column per feature
+    let metrics = [
+        DistanceMetric::Euclidean,
+        DistanceMetric::Manhattan,
+        DistanceMetric::Chebyshev,
+    ];
+    let features = [
+        CellularFeature::F1,
+        CellularFeature::F2,
+        CellularFeature::F2MinusF1,
+    ];
+    for (row, metric) in metrics.into_iter().enumerate() {
+        for (column, feature) in features.into_iter().enumerate() {
+            let texture: Rc<dyn Texture> = Rc::new(
+                WorleyTexture::new(4.)
+                    .with_seed(7)
+                    .with_feature(feature)
+                    .with_metric(metric),
+            );
+            world.add(Rc::new(Sphere::new(
+                Point3::new(2.2 * (column as f64 - 1.0), 1.0, -2.2 * row as f64),
+                1.0,
+                Rc::new(Lambertian::from(texture)),
+            )));
+        }
+    }
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(0., 10., 9.))
+        .lookat(Point3::new(0., 0.5, -2.2))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
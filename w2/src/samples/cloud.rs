@@ -0,0 +1,47 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{
+        aabb::AABB, density_grid::DensityGrid, heterogeneous_medium::HeterogeneousMedium,
+        hittable_list::HittableList, sphere::Sphere,
+    },
+    material::lambertian::Lambertian,
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn cloud(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(Color::new(0.48, 0.83, 0.53))),
+    )));
+
+    let grid = Rc::new(DensityGrid::from_turbulence(64, 4.0, 5, 1));
+    world.add(Rc::new(HeterogeneousMedium::new(
+        grid,
+        AABB::new_from_points(Point3::new(-2.5, 0.5, -2.5), Point3::new(2.5, 4.0, 2.5)),
+        8.0,
+        Color::new(0.9, 0.9, 0.9),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(13., 3., 3.))
+        .lookat(Point3::new(0., 2., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
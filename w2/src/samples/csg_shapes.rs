@@ -0,0 +1,74 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{
+        csg::Csg, cylinder::Cylinder, hittable_list::HittableList, plane::Plane, sphere::Sphere,
+        torus::Torus,
+    },
+    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal, Material},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn csg_shapes(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let red: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.7, 0.2, 0.1)));
+    let gold: Rc<dyn Material> = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.05));
+    let glass: Rc<dyn Material> = Rc::new(Dielectric::new(1.5));
+
+    // a sphere with a tube drilled through it
+    world.add(Rc::new(Csg::difference(
+        Rc::new(Sphere::new(Point3::new(0.0, 1.0, -2.5), 1.0, red.clone())),
+        Rc::new(Cylinder::new(
+            Point3::new(-2.0, 1.0, -2.5),
+            Vec3::new(4.0, 0.0, 0.0),
+            0.5,
+            red,
+        )),
+    )));
+
+    // a lens, where two spheres overlap
+    world.add(Rc::new(Csg::intersection(
+        Rc::new(Sphere::new(Point3::new(0.0, 1.0, -0.6), 1.0, glass.clone())),
+        Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.6), 1.0, glass)),
+    )));
+
+    // a ringed planet
+    world.add(Rc::new(Csg::union(
+        Rc::new(Sphere::new(Point3::new(0.0, 1.0, 2.5), 0.7, gold.clone())),
+        Rc::new(Torus::new(
+            Point3::new(0.0, 1.0, 2.5),
+            Vec3::new(0.3, 1.0, 0.2),
+            1.0,
+            0.15,
+            gold,
+        )),
+    )));
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(10., 4., 2.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
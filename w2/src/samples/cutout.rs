@@ -0,0 +1,140 @@
+use std::{error::Error, f64::consts::PI, io::Write, rc::Rc};
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere, triangle::Triangle},
+    material::{cutout::Cutout, lambertian::Lambertian, Material},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{
+        image_texture::{ColorSpace, ImageTexture, TextureFilter},
+        Texture,
+    },
+};
+
+use super::environment_light::sunny_sky;
+
+// A leaf drawn into an RGBA image, as it would come out of a paint program:
+// opaque inside, transparent outside, with a soft edge of partial alpha.
+fn leaf_image(size: u32) -> DynamicImage {
+    let image = RgbaImage::from_fn(size, size, |x, y| {
+        // s across the leaf and t from stem to tip, both in [-1, 1]
+        let s = 2.0 * (x as f64 + 0.5) / size as f64 - 1.0;
+        let t = 1.0 - 2.0 * (y as f64 + 0.5) / size as f64;
+        let half_width = 0.5 * (PI * 0.5 * (t + 1.0)).sin().max(0.0).powf(0.8);
+        let edge = (half_width - s.abs()) * size as f64 / 4.0; // in texels
+        let alpha = edge.clamp(0.0, 1.0);
+        let vein = (s.abs() * size as f64 / 3.0).min(1.0);
+        let green = 0.35 + 0.25 * vein;
+        Rgba([
+            (green * 0.4 * 255.0) as u8,
+            (green * 255.0) as u8,
+            (green * 0.15 * 255.0) as u8,
+            (alpha * 255.0) as u8,
+        ])
+    });
+    DynamicImage::ImageRgba8(image)
+}
+
+// A lattice of round holes, cut with a procedural mask rather than an image.
+#[derive(Debug)]
+struct Lattice {
+    cells: (f64, f64),
+    hole_radius: f64, // fraction of a cell
+}
+
+impl Texture for Lattice {
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        Color::new(0.8, 0.6, 0.2)
+    }
+
+    fn alpha(&self, u: f64, v: f64, _p: &Point3) -> f64 {
+        let x = (u * self.cells.0).fract() - 0.5;
+        let y = (v * self.cells.1).fract() - 0.5;
+        if x.hypot(y) < self.hole_radius {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+pub fn cutout(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.6, 0.6, 0.6))),
+    )));
+
+    // the inside of the sphere shows through the holes, and so does the sun on the floor
+    let lattice: Rc<dyn Texture> = Rc::new(Lattice {
+        cells: (24.0, 12.0),
+        hole_radius: 0.35,
+    });
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-1.3, 1.0, 0.0),
+        1.0,
+        Rc::new(Cutout::new(
+            Rc::new(Lambertian::from(lattice.clone())),
+            lattice,
+        )),
+    )));
+
+    // leaves are single quads, their shape comes from the image's alpha
+    let leaf: Rc<dyn Texture> = Rc::new(
+        ImageTexture::from_image(&leaf_image(256), ColorSpace::Srgb)
+            .with_filter(TextureFilter::Bilinear),
+    );
+    let leaf_material: Rc<dyn Material> =
+        Rc::new(Cutout::new(Rc::new(Lambertian::from(leaf.clone())), leaf));
+    for (i, angle) in [-50.0_f64, -15.0, 20.0, 55.0].iter().enumerate() {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let stem = Point3::new(1.4, 0.2 + 0.25 * i as f64, -0.3 * i as f64);
+        let along = Vec3::new(sin, cos, 0.3) * 1.6;
+        let across = Vec3::new(cos, -sin, 0.2) * 0.8;
+        let (a, b, c, d) = (
+            stem - across * 0.5,
+            stem + across * 0.5,
+            stem + across * 0.5 + along,
+            stem - across * 0.5 + along,
+        );
+        world.add(Rc::new(
+            Triangle::new(a, b, c, leaf_material.clone()).with_uvs([
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+            ]),
+        ));
+        world.add(Rc::new(
+            Triangle::new(a, c, d, leaf_material.clone()).with_uvs([
+                (0.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+            ]),
+        ));
+    }
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 3., 9.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .environment(Rc::new(sunny_sky()))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
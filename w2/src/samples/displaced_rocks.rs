@@ -0,0 +1,57 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane},
+    material::{lambertian::Lambertian, Material},
+    mesh::{subdivision::SubdivisionScheme, Mesh},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::noise_texture::NoiseTexture,
+};
+
+use super::subdivision_shapes::octahedron;
+
+pub fn displaced_rocks(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let stone: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.45, 0.4, 0.35)));
+    let noise = NoiseTexture::new(6.);
+
+    let boulder = octahedron(Point3::new(0.0, 1.2, -1.5), 1.8)
+        .subdivide(SubdivisionScheme::Loop, 2)
+        .tessellate(0.05)
+        .displace(&noise, 0.25, 0.5);
+    let pebble = Mesh::cube(Point3::new(0.0, 0.6, 1.8), 0.6)
+        .subdivide(SubdivisionScheme::CatmullClark, 2)
+        .tessellate(0.04)
+        .displace(&noise, 0.15, 0.5);
+
+    world.add(Rc::new(boulder.to_hittable_list(stone.clone()).into_bvh()));
+    world.add(Rc::new(pebble.to_hittable_list(stone).into_bvh()));
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(10., 4., 2.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::{error::Error, f64::consts::PI, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    light::environment::EnvironmentLight,
+    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::Texture,
+};
+
+// A stand-in for an HDR sky photograph: a blue gradient with a small, very bright sun.
+// Use EnvironmentLight::from_file to light a scene with an actual .hdr or .exr image.
+#[derive(Debug)]
+struct SunnySky {
+    sun_direction: Vec3,
+    cos_sun_radius: f64,
+}
+
+impl Texture for SunnySky {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+        // equirectangular layout, as expected by EnvironmentLight
+        let (theta, phi) = (v * PI, u * 2.0 * PI - PI);
+        let d = Vec3::new(
+            theta.sin() * phi.cos(),
+            -theta.cos(),
+            -theta.sin() * phi.sin(),
+        );
+        if d.dot(&self.sun_direction) > self.cos_sun_radius {
+            return Color::new(1000.0, 900.0, 750.0);
+        }
+        let t = 0.5 * (d.y + 1.0);
+        Color::new(0.35, 0.35, 0.35) * (1.0 - t) + Color::new(0.1, 0.2, 0.45) * t
+    }
+}
+
+// Also lights the other samples that need a sun.
+pub(crate) fn sunny_sky() -> EnvironmentLight {
+    let sky = SunnySky {
+        sun_direction: Vec3::new(1.0, 1.2, 0.6).unit(),
+        cos_sun_radius: 1.5_f64.to_radians().cos(),
+    };
+    EnvironmentLight::new(Rc::new(sky), (1024, 512), 1.0)
+}
+
+pub fn environment_light(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::new(Color::new(0.7, 0.3, 0.2))),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.2)),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Dielectric::new(1.5)),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 3., 9.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .environment(Rc::new(sunny_sky()))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{curve::Curve, hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::{hair::Hair, lambertian::Lambertian, Material},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn furball(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let center = Point3::new(0.0, 1.0, 0.0);
+    let radius = 0.8;
+    let fur: Rc<dyn Material> = Rc::new(Hair::new(Color::new(0.75, 0.5, 0.25), 0.1, 0.05, 1.55));
+    world.add(Rc::new(Sphere::new(center, radius, fur.clone())));
+
+    // strands of two segments, growing out of the ball and drooping under their own weight
+    let mut strands = HittableList::new();
+    for _ in 0..4000 {
+        let out_dir = Vec3::random_unit();
+        let root = center + out_dir * radius;
+        let length = 0.35 + 0.15 * rand::random::<f64>();
+        let droop = Vec3::new(0.0, -0.4, 0.0);
+        let points: Vec<Point3> = (0..7)
+            .map(|i| {
+                let s = i as f64 / 6.0;
+                root + (out_dir * s + droop * (s * s)) * length
+            })
+            .collect();
+        Curve::strand(&points, 0.012, 0.002, fur.clone())
+            .into_iter()
+            .for_each(|curve| strands.add(curve));
+    }
+    world.add(Rc::new(strands.into_bvh()));
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 2., 7.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
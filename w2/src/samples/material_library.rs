@@ -0,0 +1,102 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::lambertian::Lambertian,
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{
+        brick_texture::BrickTexture,
+        gradient_texture::{Gradient, GradientTexture},
+        noise_texture::NoiseTexture,
+        solid_color::SolidColor,
+        wood_texture::WoodTexture,
+        Texture,
+    },
+};
+
+// Common materials made only from procedural textures.
+pub fn material_library(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+    let solid = |r, g, b| -> Rc<dyn Texture> { Rc::new(SolidColor::new(Color::new(r, g, b))) };
+
+    let tiles: Rc<dyn Texture> = Rc::new(
+        BrickTexture::tiles(solid(0.75, 0.72, 0.65), solid(0.2, 0.2, 0.2), 4.0, 0.06)
+            .with_jitter(0.15, 1),
+    );
+    world.add(Rc::new(Plane::new_with_uv_scale(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        4.0,
+        Rc::new(Lambertian::from(tiles)),
+    )));
+
+    let bricks: Rc<dyn Texture> = Rc::new(
+        BrickTexture::new(
+            solid(0.55, 0.2, 0.12),
+            solid(0.6, 0.6, 0.55),
+            (4.0, 12.0),
+            0.1,
+        )
+        .with_jitter(0.3, 2),
+    );
+    world.add(Rc::new(Plane::new_with_uv_scale(
+        Point3::new(0.0, 0.0, -3.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        4.0,
+        Rc::new(Lambertian::from(bricks)),
+    )));
+
+    let wood: Rc<dyn Texture> = Rc::new(
+        WoodTexture::new(solid(0.75, 0.55, 0.3), solid(0.4, 0.22, 0.1), 0.12)
+            .with_center(Point3::new(-1.6, 0.0, -1.5))
+            .with_seed(5),
+    );
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(wood)),
+    )));
+
+    // the gradient's ends can be textures too: here it fades marble into a flat color
+    let marble: Rc<dyn Texture> = Rc::new(NoiseTexture::new(4.).with_seed(5));
+    let fade: Rc<dyn Texture> = Rc::new(GradientTexture::new(
+        solid(0.1, 0.3, 0.7),
+        marble.clone(),
+        Gradient::Linear(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0)),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(fade)),
+    )));
+
+    let glow: Rc<dyn Texture> = Rc::new(GradientTexture::new(
+        solid(0.9, 0.8, 0.2),
+        solid(0.6, 0.1, 0.1),
+        Gradient::Radial(Point3::new(2.2, 1.0, 1.0), 1.4),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(glow)),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(0., 3., 9.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::lambertian::Lambertian,
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{
+        noise_texture::{NoisePattern, NoiseTexture},
+        perlin::Fractal,
+        Texture,
+    },
+};
+
+pub fn noise_patterns(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.4, 0.4, 0.4))),
+    )));
+
+    // every sphere uses the same seed, so they differ only by pattern
+    let patterns = [
+        NoisePattern::Marble,
+        NoisePattern::Noise,
+        NoisePattern::Fbm,
+        NoisePattern::Turbulence,
+        NoisePattern::Ridged,
+        NoisePattern::Warped { strength: 4.0 },
+    ];
+    for (i, pattern) in patterns.into_iter().enumerate() {
+        let texture: Rc<dyn Texture> = Rc::new(
+            NoiseTexture::new(3.)
+                .with_seed(7)
+                .with_pattern(pattern)
+                .with_fractal(Fractal::new(6, 2.0, 0.5)),
+        );
+        let (column, row) = (i % 3, i / 3);
+        world.add(Rc::new(Sphere::new(
+            Point3::new(2.2 * (column as f64 - 1.0), 1.0, -2.2 * row as f64),
+            1.0,
+            Rc::new(Lambertian::from(texture)),
+        )));
+    }
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 8., 9.))
+        .lookat(Point3::new(0., 0.5, -1.1))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
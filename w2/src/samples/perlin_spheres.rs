@@ -2,7 +2,7 @@ use std::{error::Error, io::Write, rc::Rc};
 
 use crate::{
     camera::CameraOptionsBuilder,
-    hittable::{hittable_list::HittableList, sphere::Sphere},
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
     material::lambertian::Lambertian,
     primitive::{interval::Interval, point3::Point3, vec3::Vec3},
     texture::{noise_texture::NoiseTexture, Texture},
@@ -14,9 +14,9 @@ pub fn perlin_spheres(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
     let perlin_texture = Rc::new(NoiseTexture::new(4.));
     let perlin_material = Rc::new(Lambertian::from(perlin_texture.clone() as Rc<dyn Texture>));
 
-    let floor_sphere = Rc::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
+    let floor = Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
         perlin_material.clone(),
     ));
     let sphere = Rc::new(Sphere::new(
@@ -25,7 +25,7 @@ pub fn perlin_spheres(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
         perlin_material.clone(),
     ));
 
-    world.add(floor_sphere);
+    world.add(floor);
     world.add(sphere);
 
     let cam_opts = CameraOptionsBuilder::default()
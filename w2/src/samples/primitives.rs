@@ -0,0 +1,73 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{
+        bvh::BVHNode, cone::Cone, cylinder::Cylinder, disk::Disk, hittable_list::HittableList,
+        sphere::Sphere, torus::Torus,
+    },
+    material::{lambertian::Lambertian, metal::Metal, Material},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{image_texture::ImageTexture, Texture},
+};
+
+pub fn primitives(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    let earth_texture: Rc<dyn Texture> = Rc::new(ImageTexture::new("earthmap.jpg")?);
+    let earth: Rc<dyn Material> = Rc::new(Lambertian::from(earth_texture));
+    let metal: Rc<dyn Material> = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    world.add(Rc::new(Cylinder::new(
+        Point3::new(0.0, 0.0, -3.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        0.8,
+        earth.clone(),
+    )));
+    world.add(Rc::new(Cone::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        0.9,
+        earth.clone(),
+    )));
+    world.add(Rc::new(Torus::new(
+        Point3::new(0.0, 1.2, 3.0),
+        Vec3::new(1.0, 0.5, 0.0),
+        0.9,
+        0.3,
+        earth.clone(),
+    )));
+    world.add(Rc::new(Disk::new_annulus(
+        Point3::new(-3.0, 1.5, 0.0),
+        Vec3::new(1.0, 0.2, 0.0),
+        0.5,
+        1.5,
+        metal,
+    )));
+
+    let world = BVHNode::from(world);
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(13., 4., 3.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{
+        aabb::AABB,
+        hittable_list::HittableList,
+        plane::Plane,
+        sdf::{Blend, SdfBox, SdfCapsule, SdfObject, SdfRoundedBox, SdfSphere, SmoothUnion},
+    },
+    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn sdf_shapes(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    // a blob: sphere melting into a capsule
+    let blob = Rc::new(SmoothUnion {
+        a: Rc::new(SdfSphere {
+            center: Point3::new(0.0, 1.0, -2.5),
+            radius: 0.8,
+        }),
+        b: Rc::new(SdfCapsule {
+            a: Point3::new(0.0, 0.5, -3.5),
+            b: Point3::new(0.0, 2.0, -1.5),
+            radius: 0.3,
+        }),
+        k: 0.5,
+    });
+    world.add(Rc::new(SdfObject::new(
+        blob,
+        AABB::new_from_points(Point3::new(-1.0, 0.0, -4.0), Point3::new(1.0, 2.5, -1.0)),
+        Rc::new(Lambertian::new(Color::new(0.2, 0.4, 0.8))),
+    )));
+
+    // a glass die
+    world.add(Rc::new(SdfObject::new(
+        Rc::new(SdfRoundedBox {
+            center: Point3::new(0.0, 0.8, 0.0),
+            half_extents: Vec3::new(0.8, 0.8, 0.8),
+            radius: 0.15,
+        }),
+        AABB::new_from_points(Point3::new(-0.8, 0.0, -0.8), Point3::new(0.8, 1.6, 0.8)),
+        Rc::new(Dielectric::new(1.5)),
+    )));
+
+    // halfway between a sphere and a box
+    let morph = Rc::new(Blend {
+        a: Rc::new(SdfSphere {
+            center: Point3::new(0.0, 0.9, 2.5),
+            radius: 0.9,
+        }),
+        b: Rc::new(SdfBox {
+            center: Point3::new(0.0, 0.9, 2.5),
+            half_extents: Vec3::new(0.7, 0.7, 0.7),
+        }),
+        t: 0.5,
+    });
+    world.add(Rc::new(SdfObject::new(
+        morph,
+        AABB::new_from_points(Point3::new(-1.0, -0.1, 1.5), Point3::new(1.0, 1.9, 3.5)),
+        Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1)),
+    )));
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(10., 4., 2.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{
+        bvh::BVHNode, constant_medium::ConstantMedium, hittable_list::HittableList, sphere::Sphere,
+    },
+    material::{dielectric::Dielectric, lambertian::Lambertian},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn smoke_spheres(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(Color::new(0.48, 0.83, 0.53))),
+    )));
+
+    // a glass shell filled with blue smoke
+    let shell = Rc::new(Sphere::new(
+        Point3::new(-4.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Dielectric::new(1.5)),
+    ));
+    world.add(shell.clone());
+    world.add(Rc::new(ConstantMedium::new(
+        shell,
+        2.0,
+        Color::new(0.2, 0.4, 0.9),
+    )));
+
+    // dark smoke, rising during the frame
+    let rising = Rc::new(Sphere::new_moving(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::new(Color::new(0.0, 0.0, 0.0))),
+        Vec3::new(0.0, 0.3, 0.0),
+    ));
+    world.add(Rc::new(ConstantMedium::new(
+        rising,
+        1.0,
+        Color::new(0.0, 0.0, 0.0),
+    )));
+
+    // white smoke
+    let puff = Rc::new(Sphere::new(
+        Point3::new(4.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+    ));
+    world.add(Rc::new(ConstantMedium::new(
+        puff,
+        1.0,
+        Color::new(1.0, 1.0, 1.0),
+    )));
+
+    // thin fog over the whole scene, including the camera
+    let fog_boundary = Rc::new(Sphere::new(
+        Point3::zero(),
+        50.0,
+        Rc::new(Dielectric::new(1.5)),
+    ));
+    world.add(Rc::new(ConstantMedium::new(
+        fog_boundary,
+        0.01,
+        Color::new(1.0, 1.0, 1.0),
+    )));
+
+    let world = BVHNode::from(world);
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(20.)
+        .lookfrom(Point3::new(13., 2., 3.))
+        .lookat(Point3::new(0., 0., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
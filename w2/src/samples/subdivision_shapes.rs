@@ -0,0 +1,81 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane},
+    material::{lambertian::Lambertian, metal::Metal, Material},
+    mesh::{subdivision::SubdivisionScheme, Mesh},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub(crate) fn octahedron(center: Point3, radius: f64) -> Mesh {
+    let positions = vec![
+        center + Vec3::new(radius, 0.0, 0.0),
+        center + Vec3::new(-radius, 0.0, 0.0),
+        center + Vec3::new(0.0, radius, 0.0),
+        center + Vec3::new(0.0, -radius, 0.0),
+        center + Vec3::new(0.0, 0.0, radius),
+        center + Vec3::new(0.0, 0.0, -radius),
+    ];
+    let faces = vec![
+        vec![0, 2, 4],
+        vec![4, 2, 1],
+        vec![1, 2, 5],
+        vec![5, 2, 0],
+        vec![4, 3, 0],
+        vec![1, 3, 4],
+        vec![5, 3, 1],
+        vec![0, 3, 5],
+    ];
+    Mesh::new(positions, faces)
+}
+
+pub fn subdivision_shapes(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let red: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.7, 0.2, 0.1)));
+    let blue: Rc<dyn Material> = Rc::new(Lambertian::new(Color::new(0.2, 0.3, 0.7)));
+    let gold: Rc<dyn Material> = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+
+    // a smooth blob from a plain cube
+    let smooth =
+        Mesh::cube(Point3::new(0.0, 1.0, -2.5), 0.9).subdivide(SubdivisionScheme::CatmullClark, 3);
+    // the same cube, with the edges of its top face kept sharp
+    let mut creased = Mesh::cube(Point3::new(0.0, 1.0, 0.0), 0.9);
+    for (a, b) in [(2, 6), (6, 7), (7, 3), (3, 2)] {
+        creased.add_crease(a, b);
+    }
+    let creased = creased.subdivide(SubdivisionScheme::CatmullClark, 3);
+    // an octahedron refined towards a sphere
+    let round = octahedron(Point3::new(0.0, 1.0, 2.5), 1.8).subdivide(SubdivisionScheme::Loop, 3);
+
+    world.add(Rc::new(smooth.to_hittable_list(red).into_bvh()));
+    world.add(Rc::new(creased.to_hittable_list(blue).into_bvh()));
+    world.add(Rc::new(round.to_hittable_list(gold).into_bvh()));
+
+    let world = world.into_bvh();
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(35.)
+        .lookfrom(Point3::new(10., 4., 2.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{heightfield::Heightfield, hittable_list::HittableList, plane::Plane},
+    material::{lambertian::Lambertian, metal::Metal},
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+};
+
+pub fn terrain(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+
+    world.add(Rc::new(Heightfield::from_perlin(
+        256,
+        4.0,
+        7,
+        1,
+        Point3::new(-10.0, 0.0, -10.0),
+        Vec3::new(20.0, 3.0, 20.0),
+        Rc::new(Lambertian::new(Color::new(0.4, 0.5, 0.3))),
+    )));
+
+    // a lake filling the valleys
+    world.add(Rc::new(Plane::new(
+        Point3::new(0.0, 0.4, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Metal::new(Color::new(0.3, 0.4, 0.6), 0.05)),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(40.)
+        .lookfrom(Point3::new(12., 6., 12.))
+        .lookat(Point3::new(0., 0., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
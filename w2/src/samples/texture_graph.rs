@@ -0,0 +1,125 @@
+use std::{error::Error, io::Write, rc::Rc};
+
+use crate::{
+    camera::CameraOptionsBuilder,
+    hittable::{hittable_list::HittableList, plane::Plane, sphere::Sphere},
+    material::lambertian::Lambertian,
+    primitive::{color::Color, interval::Interval, point3::Point3, vec3::Vec3},
+    texture::{
+        channel_texture::{Channel, ChannelTexture},
+        color_ramp::ColorRamp,
+        hsv_texture::HsvTexture,
+        math_texture::{ClampTexture, InvertTexture, MathTexture},
+        mix_texture::MixTexture,
+        noise_texture::{NoisePattern, NoiseTexture},
+        solid_color::SolidColor,
+        worley::DistanceMetric,
+        worley_texture::{CellularFeature, WorleyTexture},
+        Texture,
+    },
+};
+
+// Textures wired together from simple nodes.
+pub fn texture_graph(out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let mut world = HittableList::new();
+    let solid = |r, g, b| -> Rc<dyn Texture> { Rc::new(SolidColor::new(Color::new(r, g, b))) };
+
+    // floor: Voronoi cells tinted sand
+    let cells: Rc<dyn Texture> = Rc::new(
+        WorleyTexture::new(1.5)
+            .with_seed(1)
+            .with_feature(CellularFeature::Cell),
+    );
+    let sand: Rc<dyn Texture> = Rc::new(
+        ChannelTexture::new(cells, Channel::Luminance).with_tint(Color::new(0.8, 0.7, 0.5)),
+    );
+    world.add(Rc::new(Plane::new(
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::from(sand)),
+    )));
+
+    // lava: fBm through a color ramp
+    let fbm: Rc<dyn Texture> = Rc::new(
+        NoiseTexture::new(3.)
+            .with_seed(2)
+            .with_pattern(NoisePattern::Fbm),
+    );
+    let lava: Rc<dyn Texture> = Rc::new(ColorRamp::new(
+        fbm,
+        vec![
+            (0.35, Color::new(0.02, 0.0, 0.0)),
+            (0.5, Color::new(0.6, 0.05, 0.0)),
+            (0.6, Color::new(0.95, 0.45, 0.05)),
+            (0.7, Color::new(1.0, 0.9, 0.4)),
+        ],
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(lava)),
+    )));
+
+    // cracked mud: cell borders, sharpened and inverted, mix dried mud with dark cracks
+    let borders: Rc<dyn Texture> = Rc::new(
+        WorleyTexture::new(3.)
+            .with_seed(3)
+            .with_feature(CellularFeature::F2MinusF1),
+    );
+    let sharpened: Rc<dyn Texture> = Rc::new(ClampTexture::new(
+        Rc::new(MathTexture::multiply(borders, solid(8.0, 8.0, 8.0))),
+        0.0,
+        1.0,
+    ));
+    let cracks: Rc<dyn Texture> = Rc::new(InvertTexture::new(sharpened));
+    let mud: Rc<dyn Texture> = Rc::new(MixTexture::new(
+        solid(0.55, 0.42, 0.3),
+        solid(0.08, 0.05, 0.03),
+        cracks,
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(mud)),
+    )));
+
+    // ridges tinted blue, then turned teal and desaturated around square cells
+    let ridges: Rc<dyn Texture> = Rc::new(
+        NoiseTexture::new(2.)
+            .with_seed(4)
+            .with_pattern(NoisePattern::Ridged),
+    );
+    let blue: Rc<dyn Texture> = Rc::new(
+        ChannelTexture::new(ridges, Channel::Luminance).with_tint(Color::new(0.2, 0.3, 1.0)),
+    );
+    let squares: Rc<dyn Texture> = Rc::new(
+        WorleyTexture::new(3.)
+            .with_seed(5)
+            .with_metric(DistanceMetric::Chebyshev),
+    );
+    let shifted: Rc<dyn Texture> = Rc::new(HsvTexture::new(blue.clone(), -0.15, 0.5, 1.2));
+    let patterned: Rc<dyn Texture> = Rc::new(MixTexture::new(blue, shifted, squares));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::from(patterned)),
+    )));
+
+    let cam_opts = CameraOptionsBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .vfov(30.)
+        .lookfrom(Point3::new(0., 3., 9.))
+        .lookat(Point3::new(0., 1., 0.))
+        .vup(Vec3::new(0., 1., 0.))
+        .defocus_angle(0.)
+        .time_range(Interval::new(0., 1.))
+        .build()?;
+
+    let cam = cam_opts.build();
+
+    cam.render(&world, out)?;
+    Ok(())
+}
@@ -1,13 +1,59 @@
 use std::fmt::Debug;
 
-use crate::primitive::{color::Color, point3::Point3};
+use crate::primitive::{color::Color, point3::Point3, vec3::Vec3};
 
 pub trait Texture: Debug {
     fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+
+    // Looks up the texture averaged over the area seen through one pixel.
+    // Textures that do not filter take a point sample, as do lookups without a footprint.
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, _footprint: Option<&Footprint>) -> Color {
+        self.value(u, v, p)
+    }
+
+    // Coverage at the point, from 0 (transparent) to 1 (opaque),
+    // for textures used as cutout masks. Textures without alpha are opaque.
+    fn alpha(&self, _u: f64, _v: f64, _p: &Point3) -> f64 {
+        1.0
+    }
+}
+
+// How texture coordinates and the hit point change from one pixel to the next,
+// along x and along y in the image.
+#[derive(Debug, Clone, Copy)]
+pub struct Footprint {
+    pub dudx: f64,
+    pub dvdx: f64,
+    pub dudy: f64,
+    pub dvdy: f64,
+    pub dpdx: Vec3,
+    pub dpdy: Vec3,
+}
+
+impl Footprint {
+    // Size of the footprint in texels, for a texture of width x height texels.
+    pub fn texel_width(&self, width: f64, height: f64) -> f64 {
+        let x = (self.dudx * width).hypot(self.dvdx * height);
+        let y = (self.dudy * width).hypot(self.dvdy * height);
+        x.max(y)
+    }
 }
 
+pub mod brick_texture;
+pub mod channel_texture;
 pub mod checker_texture;
+pub mod color_ramp;
+pub mod gradient_texture;
+mod hash;
+pub mod hsv_texture;
 pub mod image_texture;
-mod perlin;
+pub mod math_texture;
+pub mod mipmap;
+pub mod mix_texture;
+pub mod noise_texture;
+pub mod perlin;
 pub mod solid_color;
-pub mod noise_texture;
\ No newline at end of file
+pub mod texture_cache;
+pub mod wood_texture;
+pub mod worley;
+pub mod worley_texture;
@@ -0,0 +1,177 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{
+    hash::{splitmix64, unit_float},
+    Footprint, Texture,
+};
+
+// Bricks or tiles laid out in uv space, separated by mortar.
+// Each row is shifted by `row_offset` of a brick, 0.5 for a running bond and 0 for a grid of tiles.
+// `jitter` varies the brightness of each brick, up to that fraction, so the wall looks less uniform.
+#[derive(Debug)]
+pub struct BrickTexture {
+    brick: Rc<dyn Texture>,
+    mortar: Rc<dyn Texture>,
+    bricks: (f64, f64), // columns and rows per unit of u and v
+    mortar_width: f64,  // as a fraction of a brick's height
+    row_offset: f64,
+    jitter: f64,
+    seed: u64,
+}
+
+impl BrickTexture {
+    pub fn new(
+        brick: Rc<dyn Texture>,
+        mortar: Rc<dyn Texture>,
+        bricks: (f64, f64),
+        mortar_width: f64,
+    ) -> Self {
+        Self {
+            brick,
+            mortar,
+            bricks,
+            mortar_width,
+            row_offset: 0.5,
+            jitter: 0.0,
+            seed: 0,
+        }
+    }
+
+    // Square tiles in a grid, `count` of them across a unit of u and v.
+    pub fn tiles(
+        tile: Rc<dyn Texture>,
+        grout: Rc<dyn Texture>,
+        count: f64,
+        grout_width: f64,
+    ) -> Self {
+        Self::new(tile, grout, (count, count), grout_width).with_row_offset(0.0)
+    }
+
+    pub fn with_row_offset(mut self, row_offset: f64) -> Self {
+        self.row_offset = row_offset;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64, seed: u64) -> Self {
+        self.jitter = jitter;
+        self.seed = seed;
+        self
+    }
+
+    // The brightness of the brick at (u, v), or None in the mortar.
+    fn brick_at(&self, u: f64, v: f64) -> Option<f64> {
+        let y = v * self.bricks.1;
+        let row = y.floor();
+        let x = u * self.bricks.0 + self.row_offset * row;
+        let column = x.floor();
+
+        // distance to the nearest edge, in units of a brick's height
+        let aspect = self.bricks.1 / self.bricks.0; // width over height of a brick
+        let (fx, fy) = (x - column, y - row);
+        let edge = (fx.min(1.0 - fx) * aspect).min(fy.min(1.0 - fy));
+        if edge < 0.5 * self.mortar_width {
+            return None;
+        }
+
+        if self.jitter == 0.0 {
+            return Some(1.0);
+        }
+        let hash = splitmix64(splitmix64(self.seed ^ column as i64 as u64) ^ row as i64 as u64);
+        Some(1.0 + self.jitter * (2.0 * unit_float(hash) - 1.0))
+    }
+}
+
+impl Texture for BrickTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        match self.brick_at(u, v) {
+            Some(brightness) => self.brick.value(u, v, p) * brightness,
+            None => self.mortar.value(u, v, p),
+        }
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        match self.brick_at(u, v) {
+            Some(brightness) => self.brick.value_filtered(u, v, p, footprint) * brightness,
+            None => self.mortar.value_filtered(u, v, p, footprint),
+        }
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        match self.brick_at(u, v) {
+            Some(_) => self.brick.alpha(u, v, p),
+            None => self.mortar.alpha(u, v, p),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::texture::solid_color::SolidColor;
+
+    // 4 bricks across and 8 up a unit square, so a brick is 0.25 wide and 0.125 high
+    fn wall(jitter: f64) -> BrickTexture {
+        BrickTexture::new(
+            Rc::new(SolidColor::new(Color::new(0.6, 0.2, 0.1))),
+            Rc::new(SolidColor::new(Color::new(0.8, 0.8, 0.8))),
+            (4.0, 8.0),
+            0.1,
+        )
+        .with_jitter(jitter, 11)
+    }
+
+    #[test]
+    fn joints_are_mortar() {
+        let wall = wall(0.0);
+        // between two rows
+        assert_eq!(wall.brick_at(0.1, 0.125), None);
+        // between two bricks of the first row, and of the second, shifted by half a brick
+        assert_eq!(wall.brick_at(0.25, 0.06), None);
+        assert_eq!(wall.brick_at(0.375, 0.19), None);
+    }
+
+    #[test]
+    fn brick_centers_are_brick() {
+        let wall = wall(0.0);
+        assert_eq!(wall.brick_at(0.125, 0.0625), Some(1.0));
+        assert_eq!(wall.brick_at(0.25, 0.1875), Some(1.0));
+    }
+
+    #[test]
+    fn jitter_stays_within_range() {
+        let wall = wall(0.3);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let (u, v) = (rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0));
+            if let Some(brightness) = wall.brick_at(u, v) {
+                assert!((0.7..=1.3).contains(&brightness), "{brightness}");
+            }
+        }
+    }
+
+    #[test]
+    fn jitter_is_constant_within_a_brick() {
+        let wall = wall(0.3);
+        let mut rng = StdRng::seed_from_u64(2);
+        for row in -4..4 {
+            for column in -4..4 {
+                // the first row is not shifted, the next one is shifted by half a brick
+                let shift = if row % 2 == 0 { 0.0 } else { 0.5 };
+                let at = |fx: f64, fy: f64| {
+                    let u = (column as f64 + fx - shift) * 0.25;
+                    let v = (row as f64 + fy) * 0.125;
+                    wall.brick_at(u, v).unwrap()
+                };
+                let center = at(0.5, 0.5);
+                for _ in 0..10 {
+                    let inside = at(rng.gen_range(0.1..0.9), rng.gen_range(0.1..0.9));
+                    assert_eq!(inside, center);
+                }
+            }
+        }
+    }
+}
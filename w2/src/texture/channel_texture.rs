@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// A single number read from a texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Channel {
+    #[default]
+    Luminance,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+// Turns one channel of a texture into a color: gray, or `tint` scaled by the channel.
+// Useful to drive a MixTexture or a ColorRamp with a single channel of an image,
+// or to color a scalar pattern such as noise. The input's alpha is kept.
+#[derive(Debug)]
+pub struct ChannelTexture {
+    input: Rc<dyn Texture>,
+    channel: Channel,
+    tint: Color,
+}
+
+impl ChannelTexture {
+    pub fn new(input: Rc<dyn Texture>, channel: Channel) -> Self {
+        Self {
+            input,
+            channel,
+            tint: Color::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+impl Texture for ChannelTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    // Alpha has no filtered lookup, so it is point sampled.
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let scalar = match self.channel {
+            Channel::Alpha => self.input.alpha(u, v, p),
+            channel => {
+                let c = self.input.value_filtered(u, v, p, footprint);
+                match channel {
+                    Channel::Red => c.r,
+                    Channel::Green => c.g,
+                    Channel::Blue => c.b,
+                    _ => c.luminance(),
+                }
+            }
+        };
+        self.tint * scalar
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.input.alpha(u, v, p)
+    }
+}
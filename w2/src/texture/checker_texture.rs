@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use crate::primitive::{color::Color, point3::Point3};
 
-use super::Texture;
+use super::{Footprint, Texture};
 
 // CheckerTexture alternates between even and odd textures
 // based on the parity of the sum of x, y, z coordinates.
@@ -22,19 +22,37 @@ impl CheckerTexture {
             odd,
         }
     }
-}
 
-impl Texture for CheckerTexture {
-    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+    fn is_even(&self, p: &Point3) -> bool {
         let x_int = (self.inv_scale * p.x).floor() as i32;
         let y_int = (self.inv_scale * p.y).floor() as i32;
         let z_int = (self.inv_scale * p.z).floor() as i32;
+        (x_int + y_int + z_int) % 2 == 0
+    }
+}
 
-        let is_even = (x_int + y_int + z_int) % 2 == 0;
-        if is_even {
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        if self.is_even(p) {
             self.even.value(u, v, p)
         } else {
             self.odd.value(u, v, p)
         }
     }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        if self.is_even(p) {
+            self.even.value_filtered(u, v, p, footprint)
+        } else {
+            self.odd.value_filtered(u, v, p, footprint)
+        }
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        if self.is_even(p) {
+            self.even.alpha(u, v, p)
+        } else {
+            self.odd.alpha(u, v, p)
+        }
+    }
 }
@@ -0,0 +1,103 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// Maps the luminance of a texture to colors, interpolating linearly between stops.
+// Below the first stop and above the last, their colors are used as they are.
+// The input's alpha is kept.
+#[derive(Debug)]
+pub struct ColorRamp {
+    input: Rc<dyn Texture>,
+    stops: Vec<(f64, Color)>, // sorted by position
+}
+
+impl ColorRamp {
+    pub fn new(input: Rc<dyn Texture>, mut stops: Vec<(f64, Color)>) -> Self {
+        assert!(!stops.is_empty(), "ColorRamp needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { input, stops }
+    }
+
+    fn color_at(&self, x: f64) -> Color {
+        // the first stop past x, whose segment x falls in
+        let next = self.stops.partition_point(|(position, _)| *position <= x);
+        if next == 0 {
+            return self.stops[0].1;
+        }
+        if next == self.stops.len() {
+            return self.stops[next - 1].1;
+        }
+        let (x0, c0) = self.stops[next - 1];
+        let (x1, c1) = self.stops[next];
+        let t = (x - x0) / (x1 - x0);
+        c0 * (1.0 - t) + c1 * t
+    }
+}
+
+impl Texture for ColorRamp {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        self.color_at(self.input.value_filtered(u, v, p, footprint).luminance())
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.input.alpha(u, v, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::solid_color::SolidColor;
+
+    fn ramp(stops: Vec<(f64, Color)>) -> ColorRamp {
+        ColorRamp::new(Rc::new(SolidColor::new(Color::new(0.0, 0.0, 0.0))), stops)
+    }
+
+    fn assert_close(a: Color, b: Color) {
+        let close =
+            (a.r - b.r).abs() < 1e-9 && (a.g - b.g).abs() < 1e-9 && (a.b - b.b).abs() < 1e-9;
+        assert!(close, "{a:?} vs {b:?}");
+    }
+
+    #[test]
+    fn lookups_around_the_stops() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        // given out of order
+        let ramp = ramp(vec![(0.8, blue), (0.2, red)]);
+        assert_close(ramp.color_at(-1.0), red);
+        assert_close(ramp.color_at(0.2), red);
+        assert_close(ramp.color_at(0.5), Color::new(0.5, 0.0, 0.5));
+        assert_close(ramp.color_at(0.8), blue);
+        assert_close(ramp.color_at(2.0), blue);
+    }
+
+    #[test]
+    fn stops_at_the_same_position_make_a_hard_edge() {
+        let black = Color::new(0.0, 0.0, 0.0);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        let white = Color::new(1.0, 1.0, 1.0);
+        let ramp = ramp(vec![(0.0, black), (0.5, red), (0.5, green), (1.0, white)]);
+        // up to the edge it blends towards the first, and from it on from the second
+        assert_close(ramp.color_at(0.25), Color::new(0.5, 0.0, 0.0));
+        assert_close(ramp.color_at(0.5), green);
+        assert_close(ramp.color_at(0.75), Color::new(0.5, 1.0, 0.5));
+        assert!(ramp.color_at(0.5 - 1e-9).r > 0.99);
+    }
+
+    #[test]
+    fn a_single_stop_is_a_constant() {
+        let gray = Color::new(0.3, 0.3, 0.3);
+        let ramp = ramp(vec![(0.4, gray)]);
+        for x in [-1.0, 0.4, 0.9] {
+            assert_close(ramp.color_at(x), gray);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// Where a GradientTexture runs from its start (0) to its end (1).
+#[derive(Debug, Clone, Copy)]
+pub enum Gradient {
+    U,
+    V,
+    // along the segment between two points in space
+    Linear(Point3, Point3),
+    // outwards from a center to a radius in space
+    Radial(Point3, f64),
+}
+
+// Blends from one texture to another along a gradient,
+// clamping to the start and end textures beyond it.
+#[derive(Debug)]
+pub struct GradientTexture {
+    start: Rc<dyn Texture>,
+    end: Rc<dyn Texture>,
+    gradient: Gradient,
+}
+
+impl GradientTexture {
+    pub fn new(start: Rc<dyn Texture>, end: Rc<dyn Texture>, gradient: Gradient) -> Self {
+        Self {
+            start,
+            end,
+            gradient,
+        }
+    }
+
+    fn position(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let t = match self.gradient {
+            Gradient::U => u,
+            Gradient::V => v,
+            Gradient::Linear(from, to) => {
+                let axis = to - from;
+                let length_squared = axis.length_squared();
+                if length_squared > 0.0 {
+                    (*p - from).dot(&axis) / length_squared
+                } else {
+                    0.0
+                }
+            }
+            Gradient::Radial(center, radius) => {
+                if radius > 0.0 {
+                    (*p - center).length() / radius
+                } else {
+                    0.0
+                }
+            }
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for GradientTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let t = self.position(u, v, p);
+        self.start.value(u, v, p) * (1.0 - t) + self.end.value(u, v, p) * t
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let t = self.position(u, v, p);
+        self.start.value_filtered(u, v, p, footprint) * (1.0 - t)
+            + self.end.value_filtered(u, v, p, footprint) * t
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let t = self.position(u, v, p);
+        self.start.alpha(u, v, p) * (1.0 - t) + self.end.alpha(u, v, p) * t
+    }
+}
@@ -0,0 +1,15 @@
+// Hashing for textures that derive their randomness from where they are looked up,
+// so that nothing needs to be stored and the same seed gives the same pattern.
+
+// Steele et al.'s SplitMix64 finalizer, which spreads every input bit over the output.
+pub(super) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// A number in [0, 1) from the top 53 bits of a hash.
+pub(super) fn unit_float(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,140 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// Adjusts a texture's colors in hue, saturation and value:
+// the hue is rotated by `hue_shift` turns, and saturation and value are scaled.
+// The input's alpha is kept.
+#[derive(Debug)]
+pub struct HsvTexture {
+    input: Rc<dyn Texture>,
+    hue_shift: f64,
+    saturation: f64,
+    value: f64,
+}
+
+impl HsvTexture {
+    pub fn new(input: Rc<dyn Texture>, hue_shift: f64, saturation: f64, value: f64) -> Self {
+        Self {
+            input,
+            hue_shift,
+            saturation,
+            value,
+        }
+    }
+}
+
+impl HsvTexture {
+    fn adjust(&self, c: Color) -> Color {
+        let (h, s, val) = rgb_to_hsv(c);
+        hsv_to_rgb(
+            (h + self.hue_shift).rem_euclid(1.0),
+            (s * self.saturation).clamp(0.0, 1.0),
+            (val * self.value).max(0.0),
+        )
+    }
+}
+
+impl Texture for HsvTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        self.adjust(self.input.value_filtered(u, v, p, footprint))
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.input.alpha(u, v, p)
+    }
+}
+
+// Hue in turns, in [0, 1).
+fn rgb_to_hsv(c: Color) -> (f64, f64, f64) {
+    let max = c.r.max(c.g).max(c.b);
+    let min = c.r.min(c.g).min(c.b);
+    let chroma = max - min;
+    if max <= 0.0 {
+        return (0.0, 0.0, max);
+    }
+    let hue = if chroma <= 0.0 {
+        0.0
+    } else if max == c.r {
+        ((c.g - c.b) / chroma).rem_euclid(6.0)
+    } else if max == c.g {
+        (c.b - c.r) / chroma + 2.0
+    } else {
+        (c.r - c.g) / chroma + 4.0
+    };
+    (hue / 6.0, chroma / max, max)
+}
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> Color {
+    let chroma = v * s;
+    let sector = h * 6.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = v - chroma;
+    Color::new(r + m, g + m, b + m)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn assert_close(a: Color, b: Color) {
+        let close =
+            (a.r - b.r).abs() < 1e-9 && (a.g - b.g).abs() < 1e-9 && (a.b - b.b).abs() < 1e-9;
+        assert!(close, "{a:?} vs {b:?}");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let c = Color::new(
+                rng.gen_range(0.0..1.0),
+                rng.gen_range(0.0..1.0),
+                rng.gen_range(0.0..1.0),
+            );
+            let (h, s, v) = rgb_to_hsv(c);
+            assert!((0.0..1.0).contains(&h), "{h}");
+            assert_close(hsv_to_rgb(h, s, v), c);
+        }
+    }
+
+    #[test]
+    fn grays_have_no_saturation() {
+        for c in [
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(0.4, 0.4, 0.4),
+            Color::new(1.0, 1.0, 1.0),
+        ] {
+            let (h, s, v) = rgb_to_hsv(c);
+            assert_eq!((h, s, v), (0.0, 0.0, c.r));
+            assert_close(hsv_to_rgb(h, s, v), c);
+            // any hue gives the same gray
+            assert_close(hsv_to_rgb(0.7, s, v), c);
+        }
+    }
+
+    #[test]
+    fn hue_wraps_at_one() {
+        assert_close(hsv_to_rgb(1.0, 1.0, 1.0), Color::new(1.0, 0.0, 0.0));
+        assert_close(hsv_to_rgb(1.0, 0.5, 0.8), hsv_to_rgb(0.0, 0.5, 0.8));
+        // just below 1, the hue is back near red
+        let (h, _, _) = rgb_to_hsv(Color::new(1.0, 0.0, 1e-6));
+        assert!(h > 0.99 && h < 1.0, "{h}");
+    }
+}
@@ -1,60 +1,339 @@
-use image::{DynamicImage, GenericImageView};
+use image::DynamicImage;
 
 use crate::primitive::{color::Color, point3::Point3};
 
-use super::Texture;
+use super::{
+    mipmap::{self, MipLevel, MipMap},
+    texture_cache::TextureCache,
+    Footprint, Texture,
+};
 
+// How texels are combined at a lookup point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureFilter {
+    #[default]
+    Nearest,
+    Bilinear, // the 4 nearest texels
+    Bicubic,  // the 16 nearest texels, with Catmull-Rom weights
+}
+
+// What lookups outside [0, 1] see, for each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    #[default]
+    Clamp, // the edge texels stretch outwards
+    Repeat,
+    Mirror, // repeats, flipping every other tile
+}
+
+// How the values stored in an image file relate to the linear values used for rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorSpace {
+    // Colors encoded with the sRGB transfer curve, as in most 8 and 16 bit images.
+    // Float images are always linear, and alpha is never encoded.
+    #[default]
+    Srgb,
+    // Data used as it is stored, such as normal maps or roughness maps.
+    Linear,
+}
+
+// Where an ImageTexture's mip pyramid lives.
+#[derive(Debug)]
+enum Texels {
+    Owned(MipMap),
+    Cached(TextureCache, usize), // the cache and the image's entry in it
+}
+
+// An image mapped onto uv space, with (0, 0) at the bottom left corner of the image.
+// A mip pyramid is built at load time, for lookups covering many texels.
+// Textures from a TextureCache share it, and only build it when first looked up.
 #[derive(Debug)]
 pub struct ImageTexture {
-    image: DynamicImage,
+    texels: Texels,
+    filter: TextureFilter,
+    wrap: (WrapMode, WrapMode), // along u and v
+    // uv transform, applied as scale, then rotation (in radians, counter-clockwise), then offset
+    uv_scale: (f64, f64),
+    uv_rotation: f64,
+    uv_offset: (f64, f64),
 }
 
 impl ImageTexture {
+    // Decodes the image straight away. Scenes using an image in several places,
+    // or many large images, should get their textures from a TextureCache instead.
     pub fn new(image_path: &str) -> Result<Self, image::ImageError> {
-        let image = image::open(image_path)?;
-        let image = Self::linearize(&image).unwrap_or(image);
-        Ok(Self { image })
-    }
-
-    // We use the following formula to linearize it, as we apply gamma correction at render time.
-    // Because we use sqrt approximation, this formula should suffice,
-    // However for more accurate linearization, we should follow the sRGB standard via crates such as `palette`.
-    fn linearize(image: &DynamicImage) -> Option<DynamicImage> {
-        let img_bytes: Vec<u8> = image
-            .as_bytes()
-            .iter()
-            .cloned()
-            .map(|x| ((x as f64 / 255.0).powf(2.2) * 255.0) as u8)
+        Self::open(image_path, ColorSpace::Srgb)
+    }
+
+    // Loads an image holding data rather than colors, such as a normal map,
+    // so its values are used as they are stored.
+    pub fn new_data(image_path: &str) -> Result<Self, image::ImageError> {
+        Self::open(image_path, ColorSpace::Linear)
+    }
+
+    pub fn open(image_path: &str, color_space: ColorSpace) -> Result<Self, image::ImageError> {
+        Ok(Self::from_image(&image::open(image_path)?, color_space))
+    }
+
+    // Grayscale images are expanded to RGB, and images without alpha are opaque.
+    pub fn from_image(image: &DynamicImage, color_space: ColorSpace) -> Self {
+        Self::with_texels(Texels::Owned(Self::decode(image, color_space)))
+    }
+
+    // A texture reading entry `id` of cache, see TextureCache::image.
+    pub(super) fn cached(cache: TextureCache, id: usize) -> Self {
+        Self::with_texels(Texels::Cached(cache, id))
+    }
+
+    fn with_texels(texels: Texels) -> Self {
+        Self {
+            texels,
+            filter: TextureFilter::default(),
+            wrap: (WrapMode::default(), WrapMode::default()),
+            uv_scale: (1.0, 1.0),
+            uv_rotation: 0.0,
+            uv_offset: (0.0, 0.0),
+        }
+    }
+
+    // Converts an image to linear floats and builds its mip pyramid.
+    pub(super) fn decode(image: &DynamicImage, color_space: ColorSpace) -> MipMap {
+        let is_float = matches!(
+            image,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        );
+        let decode = color_space == ColorSpace::Srgb && !is_float;
+
+        let image = image.to_rgba32f();
+        let texels = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b, a] = pixel.0;
+                if decode {
+                    [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+                } else {
+                    [r, g, b, a]
+                }
+            })
             .collect();
+        MipMap::new(MipLevel::new(image.width(), image.height(), texels))
+    }
 
-        Some(DynamicImage::ImageRgb8(image::ImageBuffer::from_raw(
-            image.width(),
-            image.height(),
-            img_bytes,
-        )?))
+    // Width and height of the image, in texels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.level_size(0)
     }
-}
 
-impl Texture for ImageTexture {
-    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
-        if (self.image.height() == 0) || (self.image.width() == 0) {
-            return Color::new(0.0, 1.0, 1.0);
+    fn level_count(&self) -> usize {
+        match &self.texels {
+            Texels::Owned(mipmap) => mipmap.level_count(),
+            Texels::Cached(cache, id) => cache.level_count(*id),
         }
+    }
 
-        // Clamp input texture coordinates to [0,1] x [1,0]
-        let u = u.clamp(0.0, 1.0);
-        let v = 1.0 - v.clamp(0.0, 1.0); // flip V to iamge coordinates
+    // Known without decoding the image, for cached textures.
+    // Levels past the smallest one are clamped to it.
+    fn level_size(&self, level: usize) -> (u32, u32) {
+        match &self.texels {
+            Texels::Owned(mipmap) => {
+                let level = mipmap.level(level);
+                (level.width, level.height)
+            }
+            Texels::Cached(cache, id) => cache.level_size(*id, level),
+        }
+    }
 
-        let i = (u * self.image.width() as f64) as u32;
-        let j = (v * self.image.height() as f64) as u32;
-        let pixel = self.image.get_pixel(i, j);
+    // i and j must be within the level.
+    fn rgba(&self, level: usize, i: u32, j: u32) -> [f32; 4] {
+        match &self.texels {
+            Texels::Owned(mipmap) => mipmap.level(level).rgba(i, j),
+            Texels::Cached(cache, id) => cache.rgba(*id, level, i, j),
+        }
+    }
 
-        let color_scale = 1. / 255.; // RGB values are in [0, 255]
+    pub fn with_filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap_u: WrapMode, wrap_v: WrapMode) -> Self {
+        self.wrap = (wrap_u, wrap_v);
+        self
+    }
 
-        Color::new(
-            color_scale * pixel.0[0] as f64,
-            color_scale * pixel.0[1] as f64,
-            color_scale * pixel.0[2] as f64,
+    // For example, a scale of (4, 4) tiles the image 4 times over the surface with WrapMode::Repeat.
+    pub fn with_uv_transform(
+        mut self,
+        scale: (f64, f64),
+        rotation: f64,
+        offset: (f64, f64),
+    ) -> Self {
+        self.uv_scale = scale;
+        self.uv_rotation = rotation;
+        self.uv_offset = offset;
+        self
+    }
+
+    fn transform_uv(&self, u: f64, v: f64) -> (f64, f64) {
+        let (u, v) = (u * self.uv_scale.0, v * self.uv_scale.1);
+        let (sin, cos) = self.uv_rotation.sin_cos();
+        (
+            u * cos - v * sin + self.uv_offset.0,
+            u * sin + v * cos + self.uv_offset.1,
         )
     }
+
+    // Maps a texel index, possibly outside the image, to one inside it.
+    fn wrap_index(i: i64, size: u32, mode: WrapMode) -> u32 {
+        let size = size as i64;
+        let i = match mode {
+            WrapMode::Clamp => i.clamp(0, size - 1),
+            WrapMode::Repeat => i.rem_euclid(size),
+            WrapMode::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size {
+                    i
+                } else {
+                    2 * size - 1 - i
+                }
+            }
+        };
+        i as u32
+    }
+
+    // Texel (i, j) of a level of the given size, wrapped into it.
+    fn texel(&self, level: usize, size: (u32, u32), i: i64, j: i64) -> [f32; 4] {
+        self.rgba(
+            level,
+            Self::wrap_index(i, size.0, self.wrap.0),
+            Self::wrap_index(j, size.1, self.wrap.1),
+        )
+    }
+
+    // Catmull-Rom weights of the 4 texels around a point at fraction t between the middle two.
+    fn cubic_weights(t: f64) -> [f64; 4] {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        [
+            0.5 * (-t3 + 2.0 * t2 - t),
+            0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+            0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+            0.5 * (t3 - t2),
+        ]
+    }
+
+    // Looks up one mip level with the texture's filter.
+    fn lookup(&self, level: usize, u: f64, v: f64) -> Color {
+        let size = self.level_size(level);
+        if size.0 == 0 || size.1 == 0 {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+        let texel = |i, j| {
+            let [r, g, b, _] = self.texel(level, size, i, j);
+            Color::new(r, g, b)
+        };
+
+        let (u, v) = self.transform_uv(u, v);
+        // continuous texel coordinates, with texel centers at integers,
+        // and v flipped to image coordinates
+        let x = u * size.0 as f64 - 0.5;
+        let y = (1.0 - v) * size.1 as f64 - 0.5;
+
+        match self.filter {
+            TextureFilter::Nearest => texel(x.round() as i64, y.round() as i64),
+            TextureFilter::Bilinear => {
+                let (i, j) = (x.floor(), y.floor());
+                let (tx, ty) = (x - i, y - j);
+                let (i, j) = (i as i64, j as i64);
+                texel(i, j) * ((1.0 - tx) * (1.0 - ty))
+                    + texel(i + 1, j) * (tx * (1.0 - ty))
+                    + texel(i, j + 1) * ((1.0 - tx) * ty)
+                    + texel(i + 1, j + 1) * (tx * ty)
+            }
+            TextureFilter::Bicubic => {
+                let (i, j) = (x.floor(), y.floor());
+                let (wx, wy) = (Self::cubic_weights(x - i), Self::cubic_weights(y - j));
+                let (i, j) = (i as i64, j as i64);
+                let mut sum = Color::new(0.0, 0.0, 0.0);
+                for (dj, wy) in wy.iter().enumerate() {
+                    for (di, wx) in wx.iter().enumerate() {
+                        sum += texel(i + di as i64 - 1, j + dj as i64 - 1) * (wx * wy);
+                    }
+                }
+                // the negative lobes can overshoot below zero next to sharp edges
+                Color::new(sum.r.max(0.0), sum.g.max(0.0), sum.b.max(0.0))
+            }
+        }
+    }
+
+    // Alpha of the full resolution image, filtered bilinearly
+    // unless the texture uses nearest filtering: bicubic's overshoot is no use for coverage.
+    fn lookup_alpha(&self, u: f64, v: f64) -> f64 {
+        let size = self.level_size(0);
+        if size.0 == 0 || size.1 == 0 {
+            return 1.0;
+        }
+        let alpha = |i, j| self.texel(0, size, i, j)[3] as f64;
+
+        let (u, v) = self.transform_uv(u, v);
+        let x = u * size.0 as f64 - 0.5;
+        let y = (1.0 - v) * size.1 as f64 - 0.5;
+        if self.filter == TextureFilter::Nearest {
+            return alpha(x.round() as i64, y.round() as i64);
+        }
+        let (i, j) = (x.floor(), y.floor());
+        let (tx, ty) = (x - i, y - j);
+        let (i, j) = (i as i64, j as i64);
+        alpha(i, j) * ((1.0 - tx) * (1.0 - ty))
+            + alpha(i + 1, j) * (tx * (1.0 - ty))
+            + alpha(i, j + 1) * ((1.0 - tx) * ty)
+            + alpha(i + 1, j + 1) * (tx * ty)
+    }
+
+    // Trilinear lookup: blends the two mip levels whose texels are closest
+    // to `texels`, a filter width in texels of the base level.
+    fn value_with_texel_width(&self, u: f64, v: f64, texels: f64) -> Color {
+        let lod = mipmap::lod(texels, self.level_count());
+
+        let lower = lod.floor();
+        let t = lod - lower;
+        let lower = lower as usize;
+        if t == 0.0 {
+            return self.lookup(lower, u, v);
+        }
+        self.lookup(lower, u, v) * (1.0 - t) + self.lookup(lower + 1, u, v) * t
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+        self.lookup(0, u, v)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let Some(footprint) = footprint else {
+            return self.value(u, v, p);
+        };
+        // the footprint's longer side, so that minified lookups blur rather than alias,
+        // measured in texels after the uv transform
+        let (width, height) = self.dimensions();
+        let texels = footprint.texel_width(
+            width as f64 * self.uv_scale.0.abs(),
+            height as f64 * self.uv_scale.1.abs(),
+        );
+        self.value_with_texel_width(u, v, texels)
+    }
+
+    fn alpha(&self, u: f64, v: f64, _p: &Point3) -> f64 {
+        self.lookup_alpha(u, v)
+    }
+}
+
+// The exact sRGB decoding curve, with its linear segment near black.
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
 }
@@ -0,0 +1,121 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// Per channel arithmetic between two textures.
+// Products are covered where both inputs are; sums and differences keep the alpha of `a`,
+// which `b` only changes the color of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathOp {
+    Add,
+    Subtract, // a - b
+    Multiply,
+}
+
+#[derive(Debug)]
+pub struct MathTexture {
+    op: MathOp,
+    a: Rc<dyn Texture>,
+    b: Rc<dyn Texture>,
+}
+
+impl MathTexture {
+    pub fn new(op: MathOp, a: Rc<dyn Texture>, b: Rc<dyn Texture>) -> Self {
+        Self { op, a, b }
+    }
+
+    pub fn add(a: Rc<dyn Texture>, b: Rc<dyn Texture>) -> Self {
+        Self::new(MathOp::Add, a, b)
+    }
+
+    pub fn subtract(a: Rc<dyn Texture>, b: Rc<dyn Texture>) -> Self {
+        Self::new(MathOp::Subtract, a, b)
+    }
+
+    pub fn multiply(a: Rc<dyn Texture>, b: Rc<dyn Texture>) -> Self {
+        Self::new(MathOp::Multiply, a, b)
+    }
+}
+
+impl Texture for MathTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let a = self.a.value_filtered(u, v, p, footprint);
+        let b = self.b.value_filtered(u, v, p, footprint);
+        match self.op {
+            MathOp::Add => a + b,
+            MathOp::Subtract => Color::new(a.r - b.r, a.g - b.g, a.b - b.b),
+            MathOp::Multiply => a * b,
+        }
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        match self.op {
+            MathOp::Add | MathOp::Subtract => self.a.alpha(u, v, p),
+            MathOp::Multiply => self.a.alpha(u, v, p) * self.b.alpha(u, v, p),
+        }
+    }
+}
+
+// One minus each channel, turning black into white and a color into its complement.
+// Alpha is left as it is.
+#[derive(Debug)]
+pub struct InvertTexture {
+    input: Rc<dyn Texture>,
+}
+
+impl InvertTexture {
+    pub fn new(input: Rc<dyn Texture>) -> Self {
+        Self { input }
+    }
+}
+
+impl Texture for InvertTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let c = self.input.value_filtered(u, v, p, footprint);
+        Color::new(1.0 - c.r, 1.0 - c.g, 1.0 - c.b)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.input.alpha(u, v, p)
+    }
+}
+
+// Keeps each channel between min and max, for instance after adding or subtracting textures.
+#[derive(Debug)]
+pub struct ClampTexture {
+    input: Rc<dyn Texture>,
+    min: f64,
+    max: f64,
+}
+
+impl ClampTexture {
+    pub fn new(input: Rc<dyn Texture>, min: f64, max: f64) -> Self {
+        Self { input, min, max }
+    }
+}
+
+impl Texture for ClampTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let c = self.input.value_filtered(u, v, p, footprint);
+        let clamp = |x: f64| x.max(self.min).min(self.max);
+        Color::new(clamp(c.r), clamp(c.g), clamp(c.b))
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.input.alpha(u, v, p)
+    }
+}
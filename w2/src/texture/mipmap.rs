@@ -0,0 +1,113 @@
+// One level of a MipMap, with linear RGBA texels stored row by row from the top of the image.
+#[derive(Debug, Clone)]
+pub struct MipLevel {
+    pub width: u32,
+    pub height: u32,
+    texels: Vec<[f32; 4]>,
+}
+
+impl MipLevel {
+    pub fn new(width: u32, height: u32, texels: Vec<[f32; 4]>) -> Self {
+        assert_eq!(
+            texels.len(),
+            (width * height) as usize,
+            "MipLevel texel count mismatch"
+        );
+        Self {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    // Linear RGB and alpha. i and j must be within the level.
+    pub fn rgba(&self, i: u32, j: u32) -> [f32; 4] {
+        self.texels[(j * self.width + i) as usize]
+    }
+
+    // Texels from i0 to i1 (excluded) of row j.
+    pub(super) fn row(&self, j: u32, i0: u32, i1: u32) -> &[[f32; 4]] {
+        let start = (j * self.width) as usize;
+        &self.texels[start + i0 as usize..start + i1 as usize]
+    }
+
+    // Averages blocks of 2x2 texels.
+    // An odd row or column at the end is folded into the last block.
+    fn downsample(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let source = |i: u32, j: u32| self.texels[(j * self.width + i) as usize];
+        let mut texels = Vec::with_capacity((width * height) as usize);
+        for j in 0..height {
+            let rows = Self::block(j, height, self.height);
+            for i in 0..width {
+                let columns = Self::block(i, width, self.width);
+                let mut sum = [0.0; 4];
+                for y in rows.clone() {
+                    for x in columns.clone() {
+                        let texel = source(x, y);
+                        (0..4).for_each(|c| sum[c] += texel[c]);
+                    }
+                }
+                let count = (rows.len() * columns.len()) as f32;
+                texels.push(sum.map(|s| s / count));
+            }
+        }
+        Self::new(width, height, texels)
+    }
+
+    // the source texels covered by texel i of a level of size n, from a level of size source_n
+    fn block(i: u32, n: u32, source_n: u32) -> std::ops::Range<u32> {
+        let start = (2 * i).min(source_n - 1);
+        let end = if i + 1 == n { source_n } else { 2 * i + 2 };
+        start..end.max(start + 1)
+    }
+}
+
+// A pyramid of ever smaller copies of an image, each half the size of the previous one,
+// down to a single texel.
+// Lookups covering many texels read from a smaller level instead of averaging them all.
+#[derive(Debug, Clone)]
+pub struct MipMap {
+    levels: Vec<MipLevel>,
+}
+
+impl MipMap {
+    pub fn new(base: MipLevel) -> Self {
+        let count = level_sizes(base.width, base.height).len();
+        let mut levels = vec![base];
+        while levels.len() < count {
+            levels.push(levels.last().unwrap().downsample());
+        }
+        Self { levels }
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn level(&self, level: usize) -> &MipLevel {
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+}
+
+// Sizes of the levels of a pyramid over an image of width x height texels, from the largest.
+pub fn level_sizes(width: u32, height: u32) -> Vec<(u32, u32)> {
+    let mut sizes = vec![(width, height)];
+    let (mut width, mut height) = (width, height);
+    while width > 1 || height > 1 {
+        (width, height) = ((width / 2).max(1), (height / 2).max(1));
+        sizes.push((width, height));
+    }
+    sizes
+}
+
+// Level of detail for a filter `width` texels of the base level wide,
+// in a pyramid of level_count levels.
+// Fractional levels blend between the two nearest levels.
+pub fn lod(width: f64, level_count: usize) -> f64 {
+    if width <= 1.0 || !width.is_finite() {
+        return 0.0;
+    }
+    width.log2().min((level_count - 1) as f64)
+}
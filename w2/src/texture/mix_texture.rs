@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{Footprint, Texture};
+
+// Blends two textures, taking `b` where the factor texture is white and `a` where it is black.
+// Alpha is blended the same way.
+#[derive(Debug)]
+pub struct MixTexture {
+    a: Rc<dyn Texture>,
+    b: Rc<dyn Texture>,
+    factor: Rc<dyn Texture>, // read as its luminance, clamped to [0, 1]
+}
+
+impl MixTexture {
+    pub fn new(a: Rc<dyn Texture>, b: Rc<dyn Texture>, factor: Rc<dyn Texture>) -> Self {
+        Self { a, b, factor }
+    }
+}
+
+impl Texture for MixTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.value_filtered(u, v, p, None)
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let t = self
+            .factor
+            .value_filtered(u, v, p, footprint)
+            .luminance()
+            .clamp(0.0, 1.0);
+        if t <= 0.0 {
+            return self.a.value_filtered(u, v, p, footprint);
+        }
+        if t >= 1.0 {
+            return self.b.value_filtered(u, v, p, footprint);
+        }
+        self.a.value_filtered(u, v, p, footprint) * (1.0 - t)
+            + self.b.value_filtered(u, v, p, footprint) * t
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let t = self.factor.value(u, v, p).luminance().clamp(0.0, 1.0);
+        if t <= 0.0 {
+            return self.a.alpha(u, v, p);
+        }
+        if t >= 1.0 {
+            return self.b.alpha(u, v, p);
+        }
+        self.a.alpha(u, v, p) * (1.0 - t) + self.b.alpha(u, v, p) * t
+    }
+}
@@ -1,11 +1,31 @@
 use crate::primitive::{color::Color, point3::Point3};
 
-use super::{perlin::Perlin, Texture};
+use super::{
+    perlin::{Fractal, Perlin},
+    Texture,
+};
+
+// Which function of the noise a NoiseTexture shows, as a gray level.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NoisePattern {
+    // veins of turbulence across stripes along z, as in "The Next Week"
+    #[default]
+    Marble,
+    Noise, // a single octave
+    Fbm,
+    Turbulence,
+    Ridged,
+    Warped {
+        strength: f64,
+    },
+}
 
 #[derive(Debug)]
 pub struct NoiseTexture {
     noise: Perlin,
     scale: f64, // scale increases the pattern frequency
+    pattern: NoisePattern,
+    fractal: Fractal,
 }
 
 impl NoiseTexture {
@@ -13,8 +33,46 @@ impl NoiseTexture {
         Self {
             noise: Perlin::new(),
             scale,
+            pattern: NoisePattern::default(),
+            fractal: Fractal::default(),
         }
     }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.noise = Perlin::with_seed(seed);
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: NoisePattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    pub fn with_fractal(mut self, fractal: Fractal) -> Self {
+        self.fractal = fractal;
+        self
+    }
+
+    // The pattern's value at p, mapped to [0, 1].
+    fn gray(&self, p: &Point3) -> f64 {
+        let fractal = &self.fractal;
+        // fBm of a full series of octaves rarely strays beyond [-1, 1]
+        let signed = |n: f64| 0.5 * (1.0 + n);
+        let value = match self.pattern {
+            NoisePattern::Marble => {
+                let veins = self.noise.fbm(p, fractal).abs() * 10.;
+                0.5 * (1. + (veins + self.scale * p.z).sin())
+            }
+            NoisePattern::Noise => signed(self.noise.noise(*p * self.scale)),
+            NoisePattern::Fbm => signed(self.noise.fbm(&(*p * self.scale), fractal)),
+            NoisePattern::Turbulence => self.noise.fractal_turbulence(&(*p * self.scale), fractal),
+            NoisePattern::Ridged => self.noise.ridged(&(*p * self.scale), fractal),
+            NoisePattern::Warped { strength } => {
+                signed(self.noise.warped(&(*p * self.scale), fractal, strength))
+            }
+        };
+        value.clamp(0.0, 1.0)
+    }
 }
 
 impl Default for NoiseTexture {
@@ -25,7 +83,46 @@ impl Default for NoiseTexture {
 
 impl Texture for NoiseTexture {
     fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
-        Color::new(0.5, 0.5, 0.5)
-            * (1. + (self.noise.turbulence(p, 7) * 10. + self.scale * p.z).sin())
+        let gray = self.gray(p);
+        Color::new(gray, gray, gray)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn default_marble_matches_the_turbulence_marble() {
+        // the marble from "The Next Week", before the patterns had fractal settings
+        let texture = NoiseTexture::new(4.0).with_seed(9);
+        let noise = Perlin::with_seed(9);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let p = Point3::new(
+                rng.gen_range(-5.0..5.0),
+                rng.gen_range(-5.0..5.0),
+                rng.gen_range(-5.0..5.0),
+            );
+            let marble = 0.5 * (1. + (4.0 * p.z + 10. * noise.turbulence(&p, 7)).sin());
+            assert!((texture.gray(&p) - marble).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_texture() {
+        let pattern = NoisePattern::Warped { strength: 2.0 };
+        let a = NoiseTexture::new(3.0).with_seed(2).with_pattern(pattern);
+        let b = NoiseTexture::new(3.0).with_seed(2).with_pattern(pattern);
+        let c = NoiseTexture::new(3.0).with_seed(3).with_pattern(pattern);
+        let points: Vec<Point3> = (0..50)
+            .map(|i| Point3::new(i as f64 * 0.37, 0.5, i as f64 * -0.21))
+            .collect();
+        for p in &points {
+            assert_eq!(a.gray(p), b.gray(p));
+        }
+        assert!(points.iter().any(|p| a.gray(p) != c.gray(p)));
     }
 }
@@ -1,5 +1,9 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::primitive::{point3::Point3, vec3::Vec3};
 
+// Gradient noise over 3D space, and the fractal sums built from it.
+// The lattice of random gradients repeats every point_count units along each axis.
 #[derive(Debug)]
 pub struct Perlin {
     randvec: Vec<Vec3>,
@@ -8,18 +12,71 @@ pub struct Perlin {
     perm_z: Vec<usize>,
 }
 
+// How octaves of noise are summed: each octave's frequency is `lacunarity` times
+// the previous one's, and its amplitude `gain` times the previous one's.
+#[derive(Debug, Clone, Copy)]
+pub struct Fractal {
+    pub octaves: u32,
+    pub lacunarity: f64,
+    pub gain: f64,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            octaves: 7,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Fractal {
+    pub fn new(octaves: u32, lacunarity: f64, gain: f64) -> Self {
+        Self {
+            octaves,
+            lacunarity,
+            gain,
+        }
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Perlin {
-    // Ideally must be a power of 2
     const POINT_CNT: usize = 256;
+
+    // A different pattern every time.
     pub fn new() -> Self {
-        let randvec = vec![Vec3::new(0.0, 0.0, 0.0); Self::POINT_CNT]
-            .iter()
-            .map(|_| Vec3::random_uniform(-1.0, 1.0))
+        Self::with_seed(rand::random())
+    }
+
+    // The same pattern for the same seed, so that renders can be reproduced.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::seeded(seed, Self::POINT_CNT)
+    }
+
+    // point_count is rounded up to a power of 2.
+    pub fn seeded(seed: u64, point_count: usize) -> Self {
+        let point_count = point_count.max(2).next_power_of_two();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let randvec = (0..point_count)
+            .map(|_| {
+                Vec3::new(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                )
+            })
             .collect();
 
-        let perm_x = Self::generate_perm();
-        let perm_y = Self::generate_perm();
-        let perm_z = Self::generate_perm();
+        let perm_x = Self::generate_perm(&mut rng, point_count);
+        let perm_y = Self::generate_perm(&mut rng, point_count);
+        let perm_z = Self::generate_perm(&mut rng, point_count);
 
         Self {
             randvec,
@@ -39,6 +96,7 @@ impl Perlin {
         let i = p.x.floor() as i32;
         let j = p.y.floor() as i32;
         let k = p.z.floor() as i32;
+        let mask = (self.randvec.len() - 1) as i32;
         let mut c = [[[Vec3::new(0., 0., 0.); 2]; 2]; 2];
 
         #[allow(clippy::needless_range_loop)]
@@ -46,9 +104,9 @@ impl Perlin {
             for dj in 0..2 {
                 for dk in 0..2 {
                     c[di as usize][dj as usize][dk as usize] = self.randvec[self.perm_x
-                        [((i + di) & 255) as usize]
-                        ^ self.perm_y[((j + dj) & 255) as usize]
-                        ^ self.perm_z[((k + dk) & 255) as usize]];
+                        [((i + di) & mask) as usize]
+                        ^ self.perm_y[((j + dj) & mask) as usize]
+                        ^ self.perm_z[((k + dk) & mask) as usize]];
                 }
             }
         }
@@ -70,20 +128,78 @@ impl Perlin {
         accum.abs()
     }
 
-    fn generate_perm() -> Vec<usize> {
-        let p: Vec<usize> = vec![0; Self::POINT_CNT]
-            .iter()
-            .enumerate()
-            .map(|(i, _)| i)
-            .collect();
+    // Fractional Brownian motion: octaves of noise summed as they are, roughly in [-1, 1].
+    pub fn fbm(&self, p: &Point3, fractal: &Fractal) -> f64 {
+        self.octaves(p, fractal, |n| n)
+    }
+
+    // Sum of the octaves' absolute values, which creases the pattern where the noise crosses 0.
+    // Unlike turbulence(), which folds the sum only once.
+    pub fn fractal_turbulence(&self, p: &Point3, fractal: &Fractal) -> f64 {
+        self.octaves(p, fractal, f64::abs)
+    }
+
+    // Musgrave's ridged multifractal: sharp crests where the noise crosses 0,
+    // with each octave weighted by the one before, so that detail gathers on the ridges.
+    // Roughly in [0, 1].
+    pub fn ridged(&self, p: &Point3, fractal: &Fractal) -> f64 {
+        let mut accum = 0.0;
+        let mut norm = 0.0;
+        let mut temp_p = *p;
+        let mut amplitude = 1.0;
+        let mut weight = 1.0;
+
+        for _ in 0..fractal.octaves {
+            let ridge = 1.0 - self.noise(temp_p).abs();
+            let signal = ridge * ridge * weight;
+            accum += signal * amplitude;
+            norm += amplitude;
+            weight = (signal * 2.0).clamp(0.0, 1.0);
+            amplitude *= fractal.gain;
+            temp_p = temp_p * fractal.lacunarity;
+        }
+        if norm > 0.0 {
+            accum / norm
+        } else {
+            0.0
+        }
+    }
 
-        Self::permute(p, Self::POINT_CNT)
+    // fBm looked up at a point displaced by fBm itself (Quilez's domain warping),
+    // which swirls the pattern. `strength` is the displacement in noise space.
+    pub fn warped(&self, p: &Point3, fractal: &Fractal, strength: f64) -> f64 {
+        // far apart offsets, so that the three displacement components are unrelated
+        let offset = |dx: f64, dy: f64, dz: f64| *p + Vec3::new(dx, dy, dz);
+        let q = Vec3::new(
+            self.fbm(p, fractal),
+            self.fbm(&offset(5.2, 1.3, 2.8), fractal),
+            self.fbm(&offset(1.7, 9.2, 4.1), fractal),
+        );
+        self.fbm(&(*p + q * strength), fractal)
     }
 
-    fn permute(p: Vec<usize>, n: usize) -> Vec<usize> {
+    fn octaves(&self, p: &Point3, fractal: &Fractal, shape: impl Fn(f64) -> f64) -> f64 {
+        let mut accum = 0.;
+        let mut temp_p = *p;
+        let mut amplitude = 1.;
+
+        for _ in 0..fractal.octaves {
+            accum += amplitude * shape(self.noise(temp_p));
+            amplitude *= fractal.gain;
+            temp_p = temp_p * fractal.lacunarity;
+        }
+        accum
+    }
+
+    fn generate_perm(rng: &mut impl Rng, n: usize) -> Vec<usize> {
+        let p: Vec<usize> = (0..n).collect();
+        Self::permute(rng, p, n)
+    }
+
+    fn permute(rng: &mut impl Rng, p: Vec<usize>, n: usize) -> Vec<usize> {
         let mut p = p;
         for i in (0..n).rev() {
-            let target = rand::random::<usize>() % (i + 1);
+            let target = rng.gen_range(0..=i);
             p.swap(i, target);
         }
         p
@@ -114,3 +230,75 @@ impl Perlin {
         accum
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_points(seed: u64) -> Vec<Point3> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..200)
+            .map(|_| {
+                Point3::new(
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-20.0..20.0),
+                    rng.gen_range(-20.0..20.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_noise() {
+        let (a, b, c) = (
+            Perlin::with_seed(7),
+            Perlin::with_seed(7),
+            Perlin::with_seed(8),
+        );
+        let fractal = Fractal::default();
+        let points = random_points(1);
+        for p in &points {
+            assert_eq!(a.noise(*p), b.noise(*p));
+            assert_eq!(a.fbm(p, &fractal), b.fbm(p, &fractal));
+            assert_eq!(a.ridged(p, &fractal), b.ridged(p, &fractal));
+            assert_eq!(a.warped(p, &fractal, 4.0), b.warped(p, &fractal, 4.0));
+        }
+        assert!(points.iter().any(|p| a.noise(*p) != c.noise(*p)));
+    }
+
+    #[test]
+    fn point_count_is_rounded_up_to_a_power_of_two() {
+        let noise = Perlin::seeded(3, 5);
+        assert_eq!(noise.randvec.len(), 8);
+        assert_eq!(noise.perm_x.len(), 8);
+        // so the pattern repeats every 8 units
+        for p in random_points(2) {
+            let shifted = p + Vec3::new(8.0, -16.0, 24.0);
+            assert!((noise.noise(p) - noise.noise(shifted)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn default_fbm_is_the_signed_turbulence() {
+        let noise = Perlin::with_seed(4);
+        for p in random_points(3) {
+            let fbm = noise.fbm(&p, &Fractal::default());
+            assert!((fbm.abs() - noise.turbulence(&p, 7)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn ridged_stays_in_the_unit_range() {
+        let noise = Perlin::with_seed(5);
+        for fractal in [
+            Fractal::default(),
+            Fractal::new(1, 2.0, 0.5),
+            Fractal::new(5, 3.0, 0.8),
+        ] {
+            for p in random_points(4) {
+                let value = noise.ridged(&p, &fractal);
+                assert!((0.0..=1.0).contains(&value), "{value}");
+            }
+        }
+    }
+}
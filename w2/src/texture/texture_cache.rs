@@ -0,0 +1,536 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use image::{
+    error::{ParameterError, ParameterErrorKind},
+    ImageError,
+};
+
+use super::{
+    image_texture::{ColorSpace, ImageTexture},
+    mipmap::{self, MipLevel},
+};
+
+// Side of the square tiles the mip levels are split into, in texels.
+const TILE_SIZE: u32 = 64;
+const TEXEL_BYTES: usize = std::mem::size_of::<[f32; 4]>();
+// What lookups see where an image could not be read.
+const MISSING: [f32; 4] = [0.0, 1.0, 1.0, 1.0];
+
+// Shares image files between the textures that use them.
+// Each file is decoded once per color space, the first time one of its textures is looked up,
+// and its mip pyramid is split into tiles of TILE_SIZE x TILE_SIZE texels.
+// When the tiles in memory take more than the memory budget, those not used recently
+// are moved out to a scratch file, and read back from it when they are needed again,
+// so that no image is ever decoded twice.
+// Lookups cannot fail, so errors met while reading are recorded (see take_errors)
+// and the affected textures show as missing.
+// Clones refer to the same cache.
+#[derive(Debug, Clone)]
+pub struct TextureCache {
+    state: Rc<RefCell<CacheState>>,
+}
+
+#[derive(Debug)]
+struct CacheState {
+    memory_budget: usize, // in bytes
+    memory_used: usize,
+    entries: Vec<CacheEntry>,
+    ids: HashMap<(PathBuf, ColorSpace), usize>,
+    // The tiles in memory, visited in turn by a clock hand when one must go:
+    // tiles used since the hand last passed are spared once, which approximates
+    // dropping the least recently used tile.
+    resident: Vec<TileId>,
+    hand: usize,
+    scratch: Option<Scratch>,
+    errors: Vec<(PathBuf, ImageError)>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    path: PathBuf,
+    color_space: ColorSpace,
+    levels: Vec<TiledLevel>, // sized from the image's header, tiled once decoded
+    status: EntryStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryStatus {
+    Pending, // not decoded yet
+    Decoded,
+    Failed,
+}
+
+#[derive(Debug)]
+struct TiledLevel {
+    width: u32,
+    height: u32,
+    tiles: Vec<Tile>, // row by row, TILE_SIZE texels apart
+}
+
+#[derive(Debug)]
+struct Tile {
+    width: u32, // smaller than TILE_SIZE along the right and bottom edges of a level
+    texels: Option<Vec<[f32; 4]>>, // None when moved out to the scratch file
+    offset: Option<u64>, // where the tile is in the scratch file, once written
+    used: bool, // since the clock hand last passed
+    failed: bool,
+}
+
+impl Tile {
+    fn size_in_bytes(&self) -> usize {
+        self.texels
+            .as_ref()
+            .map_or(0, |texels| texels.len() * TEXEL_BYTES)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TileId {
+    entry: usize,
+    level: usize,
+    tile: usize,
+}
+
+// A file in the temporary directory holding the tiles moved out of memory.
+// Tiles never change, so each is written at most once, and the file is deleted with the cache.
+#[derive(Debug)]
+struct Scratch {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl Default for TextureCache {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
+impl TextureCache {
+    pub fn new(memory_budget: usize) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(CacheState {
+                memory_budget,
+                memory_used: 0,
+                entries: Vec::new(),
+                ids: HashMap::new(),
+                resident: Vec::new(),
+                hand: 0,
+                scratch: None,
+                errors: Vec::new(),
+            })),
+        }
+    }
+
+    // A texture showing the image at path, sharing its texels with every other texture
+    // from this cache for the same file and color space. Filter, wrap and uv transform
+    // are set on each texture separately.
+    // Only the image's header is read here, so that a missing or unsupported file is reported
+    // straight away. The texels are decoded on the first lookup.
+    pub fn image(
+        &self,
+        path: impl AsRef<Path>,
+        color_space: ColorSpace,
+    ) -> Result<ImageTexture, ImageError> {
+        let path = path.as_ref().to_path_buf();
+        let key = (path, color_space);
+        let id = self.state.borrow().ids.get(&key).copied();
+        let id = match id {
+            Some(id) => id,
+            None => {
+                let (width, height) = image::image_dimensions(&key.0)?;
+                let mut state = self.state.borrow_mut();
+                let id = state.entries.len();
+                state.entries.push(CacheEntry {
+                    path: key.0.clone(),
+                    color_space,
+                    levels: mipmap::level_sizes(width, height)
+                        .into_iter()
+                        .map(|(width, height)| TiledLevel {
+                            width,
+                            height,
+                            tiles: Vec::new(),
+                        })
+                        .collect(),
+                    status: EntryStatus::Pending,
+                });
+                state.ids.insert(key, id);
+                id
+            }
+        };
+        Ok(ImageTexture::cached(self.clone(), id))
+    }
+
+    // Memory taken by the tiles in memory at the moment, in bytes.
+    // It goes over the budget only while an image is being decoded,
+    // or when the budget is smaller than a single tile.
+    pub fn memory_used(&self) -> usize {
+        self.state.borrow().memory_used
+    }
+
+    // The errors met while decoding images or moving tiles in and out of the scratch file,
+    // with the image concerned, since the last call.
+    pub fn take_errors(&self) -> Vec<(PathBuf, ImageError)> {
+        std::mem::take(&mut self.state.borrow_mut().errors)
+    }
+
+    pub(super) fn level_count(&self, id: usize) -> usize {
+        self.state.borrow().entries[id].levels.len()
+    }
+
+    pub(super) fn level_size(&self, id: usize, level: usize) -> (u32, u32) {
+        let state = self.state.borrow();
+        let level = state.entries[id].level(level);
+        (level.width, level.height)
+    }
+
+    // Texel (i, j) of a mip level of entry id, decoding the image or reading the tile back
+    // from the scratch file if needed. i and j must be within the level.
+    pub(super) fn rgba(&self, id: usize, level: usize, i: u32, j: u32) -> [f32; 4] {
+        self.state.borrow_mut().rgba(id, level, i, j)
+    }
+}
+
+impl CacheEntry {
+    fn level(&self, level: usize) -> &TiledLevel {
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+}
+
+impl CacheState {
+    fn rgba(&mut self, id: usize, level: usize, i: u32, j: u32) -> [f32; 4] {
+        if self.entries[id].status == EntryStatus::Pending {
+            self.decode(id);
+        }
+        if self.entries[id].status == EntryStatus::Failed {
+            return MISSING;
+        }
+
+        let level = level.min(self.entries[id].levels.len() - 1);
+        let tiles_across = self.entries[id].levels[level].width.div_ceil(TILE_SIZE);
+        let tile = TileId {
+            entry: id,
+            level,
+            tile: (j / TILE_SIZE * tiles_across + i / TILE_SIZE) as usize,
+        };
+        if self.tile(tile).texels.is_none() && !self.tile(tile).failed {
+            self.read_back(tile);
+        }
+
+        let tile = self.tile_mut(tile);
+        tile.used = true;
+        let texel = match &tile.texels {
+            Some(texels) => texels[((j % TILE_SIZE) * tile.width + i % TILE_SIZE) as usize],
+            None => MISSING,
+        };
+        // the texel is copied out, so the tile may go straight away
+        self.evict();
+        texel
+    }
+
+    fn tile(&self, id: TileId) -> &Tile {
+        &self.entries[id.entry].levels[id.level].tiles[id.tile]
+    }
+
+    fn tile_mut(&mut self, id: TileId) -> &mut Tile {
+        &mut self.entries[id.entry].levels[id.level].tiles[id.tile]
+    }
+
+    fn record_error(&mut self, entry: usize, error: ImageError) {
+        let path = self.entries[entry].path.clone();
+        self.errors.push((path, error));
+    }
+
+    // Decodes the image of entry id and splits its mip pyramid into tiles, all in memory.
+    // Any that do not fit in the budget are moved out on the following evict.
+    fn decode(&mut self, id: usize) {
+        let entry = &self.entries[id];
+        let mipmap = image::open(&entry.path)
+            .map(|image| ImageTexture::decode(&image, entry.color_space))
+            .and_then(|mipmap| {
+                // the file may have been replaced since its header was read
+                let base = mipmap.level(0);
+                if (base.width, base.height) != (entry.levels[0].width, entry.levels[0].height) {
+                    return Err(ImageError::Parameter(ParameterError::from_kind(
+                        ParameterErrorKind::DimensionMismatch,
+                    )));
+                }
+                Ok(mipmap)
+            });
+        let mipmap = match mipmap {
+            Ok(mipmap) => mipmap,
+            Err(e) => {
+                self.entries[id].status = EntryStatus::Failed;
+                self.record_error(id, e);
+                return;
+            }
+        };
+
+        for l in 0..self.entries[id].levels.len() {
+            let tiles = split_into_tiles(mipmap.level(l));
+            self.memory_used += tiles.iter().map(Tile::size_in_bytes).sum::<usize>();
+            self.resident.extend((0..tiles.len()).map(|tile| TileId {
+                entry: id,
+                level: l,
+                tile,
+            }));
+            self.entries[id].levels[l].tiles = tiles;
+        }
+        self.entries[id].status = EntryStatus::Decoded;
+    }
+
+    // Moves tiles out of memory until within the budget.
+    fn evict(&mut self) {
+        while self.memory_used > self.memory_budget && !self.resident.is_empty() {
+            self.hand %= self.resident.len();
+            let id = self.resident[self.hand];
+            let tile = self.tile_mut(id);
+            if tile.used {
+                tile.used = false;
+                self.hand += 1;
+                continue;
+            }
+            if tile.offset.is_none() {
+                if let Err(e) = self.write_out(id) {
+                    // the texels are lost, rather than going over the budget
+                    self.tile_mut(id).failed = true;
+                    self.record_error(id.entry, ImageError::IoError(e));
+                }
+            }
+            let tile = self.tile_mut(id);
+            let size = tile.size_in_bytes();
+            tile.texels = None;
+            self.memory_used -= size;
+            self.resident.swap_remove(self.hand);
+        }
+    }
+
+    fn write_out(&mut self, id: TileId) -> std::io::Result<()> {
+        let bytes: Vec<u8> = self
+            .tile(id)
+            .texels
+            .iter()
+            .flatten()
+            .flatten()
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        let scratch = match &mut self.scratch {
+            Some(scratch) => scratch,
+            None => self.scratch.insert(Scratch::create()?),
+        };
+        let offset = scratch.len;
+        scratch.file.seek(SeekFrom::Start(offset))?;
+        scratch.file.write_all(&bytes)?;
+        scratch.len += bytes.len() as u64;
+        self.tile_mut(id).offset = Some(offset);
+        Ok(())
+    }
+
+    fn read_back(&mut self, id: TileId) {
+        match self.read_tile(id) {
+            Ok(texels) => {
+                self.memory_used += texels.len() * TEXEL_BYTES;
+                self.tile_mut(id).texels = Some(texels);
+                self.resident.push(id);
+            }
+            Err(e) => {
+                self.tile_mut(id).failed = true;
+                self.record_error(id.entry, ImageError::IoError(e));
+            }
+        }
+    }
+
+    fn read_tile(&mut self, id: TileId) -> std::io::Result<Vec<[f32; 4]>> {
+        let (offset, texel_count) = {
+            let level = &self.entries[id.entry].levels[id.level];
+            let tile = &level.tiles[id.tile];
+            let rows = tile_extent(
+                id.tile as u32 / level.width.div_ceil(TILE_SIZE),
+                level.height,
+            );
+            (tile.offset, (tile.width * rows) as usize)
+        };
+        let (Some(offset), Some(scratch)) = (offset, &mut self.scratch) else {
+            return Err(std::io::Error::other("tile missing from the scratch file"));
+        };
+        let mut bytes = vec![0; texel_count * TEXEL_BYTES];
+        scratch.file.seek(SeekFrom::Start(offset))?;
+        scratch.file.read_exact(&mut bytes)?;
+        Ok(bytes
+            .chunks_exact(TEXEL_BYTES)
+            .map(|texel| {
+                let channel =
+                    |c: usize| f32::from_le_bytes(texel[4 * c..4 * c + 4].try_into().unwrap());
+                [channel(0), channel(1), channel(2), channel(3)]
+            })
+            .collect())
+    }
+}
+
+impl Scratch {
+    fn create() -> std::io::Result<Self> {
+        // several caches may be alive at once, in this process and others
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "w2-texture-cache-{}-{}.tiles",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self { path, file, len: 0 })
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        // nothing useful to do if it fails, the file is in the temporary directory anyway
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Number of texels of tile index n along an axis of the given size.
+fn tile_extent(n: u32, size: u32) -> u32 {
+    (size - n * TILE_SIZE).min(TILE_SIZE)
+}
+
+fn split_into_tiles(level: &MipLevel) -> Vec<Tile> {
+    let (across, down) = (
+        level.width.div_ceil(TILE_SIZE),
+        level.height.div_ceil(TILE_SIZE),
+    );
+    let mut tiles = Vec::with_capacity((across * down) as usize);
+    for tj in 0..down {
+        let rows = tile_extent(tj, level.height);
+        for ti in 0..across {
+            let width = tile_extent(ti, level.width);
+            let i0 = ti * TILE_SIZE;
+            let texels = (0..rows)
+                .flat_map(|y| level.row(tj * TILE_SIZE + y, i0, i0 + width))
+                .copied()
+                .collect();
+            tiles.push(Tile {
+                width,
+                texels: Some(texels),
+                offset: None,
+                used: false,
+                failed: false,
+            });
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitive::point3::Point3,
+        texture::{image_texture::TextureFilter, Texture},
+    };
+
+    // Writes a 16 bit image whose texels all differ, and returns its path.
+    fn write_image(name: &str, width: u32, height: u32) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "w2-texture-cache-test-{}-{name}.png",
+            std::process::id()
+        ));
+        let image = image::ImageBuffer::from_fn(width, height, |i, j| {
+            image::Rgb([(i * 97) as u16, (j * 89) as u16, ((i + j) * 61) as u16])
+        });
+        image.save(&path).unwrap();
+        path
+    }
+
+    // uv of the center of texel (i, j).
+    fn texel_uv(i: u32, j: u32, width: u32, height: u32) -> (f64, f64) {
+        (
+            (i as f64 + 0.5) / width as f64,
+            1.0 - (j as f64 + 0.5) / height as f64,
+        )
+    }
+
+    #[test]
+    fn tiles_moved_out_read_back_the_same() {
+        let (width, height) = (200, 150);
+        let path = write_image("budget", width, height);
+        let reference = ImageTexture::open(path.to_str().unwrap(), ColorSpace::Linear).unwrap();
+        // room for a few tiles only
+        let budget = 5 * (TILE_SIZE * TILE_SIZE) as usize * TEXEL_BYTES;
+        let cache = TextureCache::new(budget);
+        let texture = cache
+            .image(&path, ColorSpace::Linear)
+            .unwrap()
+            .with_filter(TextureFilter::Nearest);
+
+        let p = Point3::zero();
+        for pass in 0..2 {
+            for j in (0..height).step_by(7) {
+                for i in (0..width).step_by(5) {
+                    let (u, v) = texel_uv(i, j, width, height);
+                    let expected = reference.value(u, v, &p);
+                    let value = texture.value(u, v, &p);
+                    assert_eq!(
+                        (value.r, value.g, value.b),
+                        (expected.r, expected.g, expected.b),
+                        "texel ({i}, {j}) on pass {pass}"
+                    );
+                    assert!(cache.memory_used() <= budget);
+                }
+            }
+        }
+        assert!(cache.take_errors().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn files_are_shared_and_decoded_once() {
+        let path = write_image("shared", 70, 70);
+        let cache = TextureCache::default();
+        let a = cache.image(&path, ColorSpace::Srgb).unwrap();
+        let b = cache.image(&path, ColorSpace::Srgb).unwrap();
+        assert_eq!(cache.memory_used(), 0);
+
+        let p = Point3::zero();
+        a.value(0.5, 0.5, &p);
+        let used = cache.memory_used();
+        // every level of the pyramid, 70 x 70 down to 1 x 1
+        let texels: u32 = mipmap::level_sizes(70, 70).iter().map(|(w, h)| w * h).sum();
+        assert_eq!(used, texels as usize * TEXEL_BYTES);
+        // the file is gone, but b reads the texels decoded for a
+        std::fs::remove_file(&path).unwrap();
+        b.value(0.1, 0.9, &p);
+        assert_eq!(cache.memory_used(), used);
+        assert!(cache.take_errors().is_empty());
+    }
+
+    #[test]
+    fn decode_errors_are_recorded() {
+        let path = write_image("broken", 8, 8);
+        let cache = TextureCache::default();
+        let texture = cache.image(&path, ColorSpace::Srgb).unwrap();
+        // replaced by a file of another size before the first lookup
+        write_image("broken", 4, 4);
+
+        let value = texture.value(0.5, 0.5, &Point3::zero());
+        assert_eq!((value.r, value.g, value.b), (0.0, 1.0, 1.0));
+        let errors = cache.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, path);
+        // recorded once, without trying again on every lookup
+        texture.value(0.2, 0.2, &Point3::zero());
+        assert!(cache.take_errors().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+}
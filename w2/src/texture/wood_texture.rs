@@ -0,0 +1,82 @@
+use std::rc::Rc;
+
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{
+    perlin::{Fractal, Perlin},
+    Footprint, Texture,
+};
+
+// Growth rings around a trunk running parallel to the y axis through `center`,
+// blending from the `early` texture (light spring wood) to the `late` texture
+// (dark summer wood) across each ring.
+// Noise displaces the rings so that they wander like real grain.
+#[derive(Debug)]
+pub struct WoodTexture {
+    early: Rc<dyn Texture>,
+    late: Rc<dyn Texture>,
+    ring_spacing: f64,
+    center: Point3,  // a point on the trunk's axis
+    distortion: f64, // how far the rings wander, in rings
+    noise: Perlin,
+    fractal: Fractal,
+}
+
+impl WoodTexture {
+    pub fn new(early: Rc<dyn Texture>, late: Rc<dyn Texture>, ring_spacing: f64) -> Self {
+        Self {
+            early,
+            late,
+            ring_spacing,
+            center: Point3::zero(),
+            distortion: 0.5,
+            noise: Perlin::new(),
+            fractal: Fractal::new(4, 2.0, 0.5),
+        }
+    }
+
+    pub fn with_center(mut self, center: Point3) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn with_distortion(mut self, distortion: f64) -> Self {
+        self.distortion = distortion;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.noise = Perlin::with_seed(seed);
+        self
+    }
+
+    // How far across its ring the point is: 0 for early wood, 1 for late wood.
+    fn ring(&self, p: &Point3) -> f64 {
+        let d = *p - self.center;
+        // stretched along the trunk, as grain is
+        let q = Point3::new(d.x, d.y * 0.1, d.z) / self.ring_spacing;
+        let radius = d.x.hypot(d.z) / self.ring_spacing;
+        let ring = (radius + self.distortion * self.noise.fbm(&q, &self.fractal)).rem_euclid(1.0);
+        // late wood forms in a narrow band at the end of the year
+        let t = ((ring - 0.6) / 0.3).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let t = self.ring(p);
+        self.early.value(u, v, p) * (1.0 - t) + self.late.value(u, v, p) * t
+    }
+
+    fn value_filtered(&self, u: f64, v: f64, p: &Point3, footprint: Option<&Footprint>) -> Color {
+        let t = self.ring(p);
+        self.early.value_filtered(u, v, p, footprint) * (1.0 - t)
+            + self.late.value_filtered(u, v, p, footprint) * t
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let t = self.ring(p);
+        self.early.alpha(u, v, p) * (1.0 - t) + self.late.alpha(u, v, p) * t
+    }
+}
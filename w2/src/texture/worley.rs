@@ -0,0 +1,215 @@
+use crate::primitive::{point3::Point3, vec3::Vec3};
+
+use super::hash::{splitmix64, unit_float};
+
+// How far a point is from a feature point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    #[default]
+    Euclidean, // round cells
+    Manhattan, // diamond shaped cells
+    Chebyshev, // square cells
+}
+
+impl DistanceMetric {
+    pub fn distance(&self, d: &Vec3) -> f64 {
+        match self {
+            DistanceMetric::Euclidean => d.length(),
+            DistanceMetric::Manhattan => d.x.abs() + d.y.abs() + d.z.abs(),
+            DistanceMetric::Chebyshev => d.x.abs().max(d.y.abs()).max(d.z.abs()),
+        }
+    }
+}
+
+// Distances from a point to the nearest feature points of a Worley field.
+#[derive(Debug, Clone, Copy)]
+pub struct Cellular {
+    pub f1: f64, // to the nearest feature point
+    pub f2: f64, // to the second nearest
+    // random number in [0, 1) shared by every point of the nearest feature point's cell,
+    // to tell the cells of the Voronoi diagram apart
+    pub cell: f64,
+}
+
+// Worley's cellular noise: one feature point jittered within every unit cube of space,
+// and values that depend on the distance to the nearest ones.
+// Feature points are hashed from the cube and the seed, so nothing is stored.
+#[derive(Debug, Clone, Copy)]
+pub struct Worley {
+    seed: u64,
+}
+
+impl Worley {
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn cellular(&self, p: &Point3, metric: DistanceMetric) -> Cellular {
+        let (i, j, k) = (p.x.floor() as i64, p.y.floor() as i64, p.z.floor() as i64);
+        let mut nearest = Cellular {
+            f1: f64::INFINITY,
+            f2: f64::INFINITY,
+            cell: 0.0,
+        };
+
+        // Visit shells of cubes around p's cube, nearest first.
+        // A cube in shell r + 1 is at least r away along one axis, for every metric,
+        // so once F2 is within r no farther cube can hold one of the two nearest points.
+        // That takes two shells for Euclidean and Chebyshev, and up to three for Manhattan.
+        let mut r = 0i64;
+        loop {
+            for di in -r..=r {
+                for dj in -r..=r {
+                    for dk in -r..=r {
+                        if di.abs().max(dj.abs()).max(dk.abs()) != r {
+                            continue; // in an inner shell
+                        }
+                        // skip cubes whose nearest face is already farther than F2
+                        let gap = |d: i64, c: i64, x: f64| match d.signum() {
+                            1 => (c + d) as f64 - x,
+                            -1 => x - (c + d + 1) as f64,
+                            _ => 0.0,
+                        };
+                        let gap = Vec3::new(gap(di, i, p.x), gap(dj, j, p.y), gap(dk, k, p.z));
+                        if metric.distance(&gap) >= nearest.f2 {
+                            continue;
+                        }
+
+                        let (feature, cell) = self.feature_point((i + di, j + dj, k + dk));
+                        let distance = metric.distance(&(feature - *p));
+                        if distance < nearest.f1 {
+                            nearest.f2 = nearest.f1;
+                            nearest.f1 = distance;
+                            nearest.cell = cell;
+                        } else if distance < nearest.f2 {
+                            nearest.f2 = distance;
+                        }
+                    }
+                }
+            }
+            if nearest.f2 <= r as f64 {
+                return nearest;
+            }
+            r += 1;
+        }
+    }
+
+    // The feature point of a cube, and the random number of its cell.
+    fn feature_point(&self, cube: (i64, i64, i64)) -> (Point3, f64) {
+        let mut hash = self.hash(cube);
+        let mut next = || {
+            hash = splitmix64(hash);
+            unit_float(hash)
+        };
+        let feature = Point3::new(
+            cube.0 as f64 + next(),
+            cube.1 as f64 + next(),
+            cube.2 as f64 + next(),
+        );
+        (feature, next())
+    }
+
+    fn hash(&self, (i, j, k): (i64, i64, i64)) -> u64 {
+        let mut h = splitmix64(self.seed);
+        for c in [i, j, k] {
+            h = splitmix64(h ^ c as u64);
+        }
+        h
+    }
+}
+
+impl Default for Worley {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const METRICS: [DistanceMetric; 3] = [
+        DistanceMetric::Euclidean,
+        DistanceMetric::Manhattan,
+        DistanceMetric::Chebyshev,
+    ];
+
+    fn random_points(seed: u64) -> Vec<Point3> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..500)
+            .map(|_| {
+                Point3::new(
+                    rng.gen_range(-50.0..50.0),
+                    rng.gen_range(-50.0..50.0),
+                    rng.gen_range(-50.0..50.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_cells() {
+        let (a, b, c) = (
+            Worley::with_seed(3),
+            Worley::with_seed(3),
+            Worley::with_seed(4),
+        );
+        let points = random_points(1);
+        for p in &points {
+            let (x, y) = (
+                a.cellular(p, DistanceMetric::Euclidean),
+                b.cellular(p, DistanceMetric::Euclidean),
+            );
+            assert_eq!((x.f1, x.f2, x.cell), (y.f1, y.f2, y.cell));
+        }
+        assert!(points.iter().any(|p| {
+            a.cellular(p, DistanceMetric::Euclidean).f1
+                != c.cellular(p, DistanceMetric::Euclidean).f1
+        }));
+    }
+
+    #[test]
+    fn f1_is_at_most_f2() {
+        let worley = Worley::with_seed(5);
+        for metric in METRICS {
+            for p in random_points(2) {
+                let cellular = worley.cellular(&p, metric);
+                assert!(cellular.f1 <= cellular.f2, "{cellular:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn matches_a_brute_force_search() {
+        let worley = Worley::with_seed(6);
+        for metric in METRICS {
+            for p in random_points(3) {
+                // every cube within three of p's, farther than Manhattan F2 can ever reach
+                let (i, j, k) = (p.x.floor() as i64, p.y.floor() as i64, p.z.floor() as i64);
+                let mut distances = Vec::new();
+                for di in -3..=3 {
+                    for dj in -3..=3 {
+                        for dk in -3..=3 {
+                            let (feature, _) = worley.feature_point((i + di, j + dj, k + dk));
+                            distances.push(metric.distance(&(feature - p)));
+                        }
+                    }
+                }
+                distances.sort_by(f64::total_cmp);
+
+                let cellular = worley.cellular(&p, metric);
+                assert_eq!(
+                    (cellular.f1, cellular.f2),
+                    (distances[0], distances[1]),
+                    "{metric:?} at {p:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::primitive::{color::Color, point3::Point3};
+
+use super::{
+    worley::{DistanceMetric, Worley},
+    Texture,
+};
+
+// Which function of the cellular distances a WorleyTexture shows, as a gray level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellularFeature {
+    #[default]
+    F1, // dark spots around the feature points, like pebbles or scales
+    F2,
+    F2MinusF1, // dark lines along the cell borders, like cracked earth
+    Cell,      // a flat random gray per cell: the Voronoi diagram
+}
+
+#[derive(Debug)]
+pub struct WorleyTexture {
+    noise: Worley,
+    scale: f64, // scale increases the number of cells
+    feature: CellularFeature,
+    metric: DistanceMetric,
+}
+
+impl WorleyTexture {
+    pub fn new(scale: f64) -> Self {
+        Self {
+            noise: Worley::new(),
+            scale,
+            feature: CellularFeature::default(),
+            metric: DistanceMetric::default(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.noise = Worley::with_seed(seed);
+        self
+    }
+
+    pub fn with_feature(mut self, feature: CellularFeature) -> Self {
+        self.feature = feature;
+        self
+    }
+
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+}
+
+impl Default for WorleyTexture {
+    fn default() -> Self {
+        Self::new(1.)
+    }
+}
+
+impl Texture for WorleyTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let cellular = self.noise.cellular(&(*p * self.scale), self.metric);
+        let gray = match self.feature {
+            CellularFeature::F1 => cellular.f1,
+            CellularFeature::F2 => cellular.f2,
+            CellularFeature::F2MinusF1 => cellular.f2 - cellular.f1,
+            CellularFeature::Cell => cellular.cell,
+        }
+        .clamp(0.0, 1.0);
+        Color::new(gray, gray, gray)
+    }
+}
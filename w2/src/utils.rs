@@ -1,3 +0,0 @@
-pub fn deg_to_rad(degrees: f64) -> f64 {
-    degrees * std::f64::consts::PI / 180.0
-}